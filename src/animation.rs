@@ -0,0 +1,442 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::drawable::Color;
+use crate::events::{Event, TickEvent};
+
+/// The step used to integrate springs, in seconds.
+const SPRING_STEP: f32 = 1.0 / 240.0;
+/// Springs settle once their distance to the target and their speed fall below this.
+const SPRING_REST: f32 = 0.001;
+
+/// A value that can be animated.
+///
+/// Animations interpolate each component of the value independently.
+pub trait Animatable: Copy {
+    fn components(self) -> Vec<f32>;
+    fn from_components(components: &[f32]) -> Self;
+}
+
+impl Animatable for f32 {
+    fn components(self) -> Vec<f32> {vec![self]}
+    fn from_components(components: &[f32]) -> Self {components[0]}
+}
+
+impl Animatable for (f32, f32) {
+    fn components(self) -> Vec<f32> {vec![self.0, self.1]}
+    fn from_components(components: &[f32]) -> Self {(components[0], components[1])}
+}
+
+impl Animatable for Color {
+    fn components(self) -> Vec<f32> {vec![self.0 as f32, self.1 as f32, self.2 as f32, self.3 as f32]}
+    fn from_components(components: &[f32]) -> Self {
+        let channel = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+        Color(channel(components[0]), channel(components[1]), channel(components[2]), channel(components[3]))
+    }
+}
+
+/// Returns the value between `from` and `to` at `t`, where `t` is 0.0 at `from` and 1.0 at `to`.
+pub fn lerp<T: Animatable>(from: T, to: T, t: f32) -> T {
+    let components = from.components().into_iter().zip(to.components())
+        .map(|(a, b)| a + (b - a) * t).collect::<Vec<_>>();
+    T::from_components(&components)
+}
+
+/// An easing curve, mapping the progress of a tween to the progress of its value.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Overshoots the target slightly before settling.
+    BackOut,
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    /// Returns the eased progress for a linear progress `t` between 0.0 and 1.0.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::CubicInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::BackOut => {
+                let c = 1.70158;
+                1.0 + (c + 1.0) * (t - 1.0).powi(3) + c * (t - 1.0).powi(2)
+            },
+            Easing::Custom(ease) => ease(t),
+        }
+    }
+}
+
+/// The physical parameters of a spring animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+}
+
+impl Spring {
+    pub const GENTLE: Spring = Spring{stiffness: 120.0, damping: 14.0, mass: 1.0};
+    pub const WOBBLY: Spring = Spring{stiffness: 180.0, damping: 12.0, mass: 1.0};
+    pub const STIFF: Spring = Spring{stiffness: 210.0, damping: 20.0, mass: 1.0};
+
+    pub fn new(stiffness: f32, damping: f32) -> Self {
+        Spring{stiffness, damping, mass: 1.0}
+    }
+}
+
+impl Default for Spring {
+    fn default() -> Self {Spring{stiffness: 170.0, damping: 26.0, mass: 1.0}}
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Segment<T: Animatable> {
+    Tween{to: T, duration: Duration, easing: Easing},
+    Spring{to: T, spring: Spring},
+    Wait(Duration),
+}
+
+/// The segment being played, with the value it started from and the time spent in it.
+#[derive(Debug, Clone, Copy)]
+struct Active<T: Animatable> {
+    segment: Segment<T>,
+    from: T,
+    elapsed: f32,
+}
+
+/// An animated value, driven by [`TickEvent`]s.
+///
+/// Plays a sequence of tweens, springs and pauses, each starting from the value the previous one ended at.
+/// Starting a new animation with [`Animation::tween`] or [`Animation::spring`] cancels the sequence,
+/// continuing from the current value, and springs keep the current velocity so retargeting stays smooth.
+///
+///```rust
+/// # use std::time::Duration;
+/// # use roost_ui::animation::{Animation, Easing};
+/// let mut opacity = Animation::new(0.0);
+/// opacity.tween(1.0, Duration::from_millis(200), Easing::CubicOut)
+///     .then_wait(Duration::from_secs(1))
+///     .then_tween(0.0, Duration::from_millis(200), Easing::CubicIn);
+/// opacity.advance(Duration::from_millis(100));
+/// assert!(opacity.value() > 0.5);
+///```
+///
+/// In a component, pass every event to [`Animation::tick`] and read [`Animation::value`] when drawing.
+#[derive(Debug, Clone)]
+pub struct Animation<T: Animatable> {
+    value: T,
+    velocity: Vec<f32>,
+    active: Option<Active<T>>,
+    queue: VecDeque<Segment<T>>,
+}
+
+impl<T: Animatable> Animation<T> {
+    pub fn new(value: T) -> Self {
+        Animation{value, velocity: vec![0.0; value.components().len()], active: None, queue: VecDeque::new()}
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> T {self.value}
+
+    /// Returns `true` while a tween, spring or pause is playing or queued.
+    pub fn is_running(&self) -> bool {self.active.is_some() || !self.queue.is_empty()}
+
+    /// Cancels the animation and jumps to `value`.
+    pub fn set(&mut self, value: T) {
+        self.cancel();
+        self.value = value;
+    }
+
+    /// Cancels the sequence, starting a tween from the current value to `to`.
+    pub fn tween(&mut self, to: T, duration: Duration, easing: Easing) -> &mut Self {
+        self.restart(Segment::Tween{to, duration, easing})
+    }
+
+    /// Cancels the sequence, starting a spring from the current value and velocity towards `to`.
+    pub fn spring(&mut self, to: T, spring: Spring) -> &mut Self {
+        let velocity = std::mem::take(&mut self.velocity);
+        self.restart(Segment::Spring{to, spring});
+        self.velocity = velocity;
+        self
+    }
+
+    /// Queues a tween to `to`, starting when the sequence before it ends.
+    pub fn then_tween(&mut self, to: T, duration: Duration, easing: Easing) -> &mut Self {
+        self.queue.push_back(Segment::Tween{to, duration, easing});
+        self
+    }
+
+    /// Queues a spring towards `to`, starting when the sequence before it ends.
+    pub fn then_spring(&mut self, to: T, spring: Spring) -> &mut Self {
+        self.queue.push_back(Segment::Spring{to, spring});
+        self
+    }
+
+    /// Queues a pause.
+    pub fn then_wait(&mut self, duration: Duration) -> &mut Self {
+        self.queue.push_back(Segment::Wait(duration));
+        self
+    }
+
+    /// Stops the animation at the current value.
+    pub fn cancel(&mut self) {
+        self.active = None;
+        self.queue.clear();
+        self.velocity.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    /// Stops the animation, jumping to the value the sequence would end at.
+    pub fn finish(&mut self) {
+        let target = self.active.iter().map(|active| active.segment).chain(self.queue.iter().copied())
+            .rev().find_map(|segment| match segment {
+                Segment::Tween{to, ..} | Segment::Spring{to, ..} => Some(to),
+                Segment::Wait(_) => None,
+            });
+        self.cancel();
+        if let Some(target) = target {
+            self.value = target;
+        }
+    }
+
+    /// Advances the animation if `event` is a [`TickEvent`], returning `true` if the value may have changed.
+    pub fn tick(&mut self, event: &dyn Event) -> bool {
+        match event.downcast_ref::<TickEvent>() {
+            Some(tick) => self.advance(tick.delta),
+            None => false,
+        }
+    }
+
+    /// Advances the animation by `delta`, returning `true` if the value may have changed.
+    pub fn advance(&mut self, delta: Duration) -> bool {
+        if !self.is_running() { return false; }
+        let mut remaining = delta.as_secs_f32();
+        while let Some(mut active) = self.active.take().or_else(|| self.queue.pop_front().map(|segment|
+            Active{segment, from: self.value, elapsed: 0.0}
+        )) {
+
+            let finished = match active.segment {
+                Segment::Tween{to, duration, easing} => {
+                    let duration = duration.as_secs_f32();
+                    active.elapsed += remaining;
+                    let t = match duration > 0.0 {
+                        true => active.elapsed / duration,
+                        false => 1.0,
+                    };
+                    self.value = lerp(active.from, to, easing.apply(t));
+                    remaining = (active.elapsed - duration).max(0.0);
+                    t >= 1.0
+                },
+                Segment::Wait(duration) => {
+                    let duration = duration.as_secs_f32();
+                    active.elapsed += remaining;
+                    remaining = (active.elapsed - duration).max(0.0);
+                    active.elapsed >= duration
+                },
+                Segment::Spring{to, spring} => match self.integrate(to, spring, remaining) {
+                    Some(left) => {
+                        remaining = left;
+                        true
+                    },
+                    None => false,
+                },
+            };
+
+            if !finished {
+                self.active = Some(active);
+                break;
+            }
+            self.velocity.iter_mut().for_each(|v| *v = 0.0);
+        }
+        true
+    }
+
+    fn restart(&mut self, segment: Segment<T>) -> &mut Self {
+        self.cancel();
+        self.queue.push_back(segment);
+        self
+    }
+
+    /// Moves the value towards `to` for up to `seconds`, returning the time left once the spring has settled.
+    fn integrate(&mut self, to: T, spring: Spring, seconds: f32) -> Option<f32> {
+        let target = to.components();
+        let mut position = self.value.components();
+        let steps = (seconds / SPRING_STEP).ceil().max(1.0) as usize;
+        let step = seconds / steps as f32;
+        for i in 1..=steps {
+            for ((x, v), target) in position.iter_mut().zip(self.velocity.iter_mut()).zip(&target) {
+                let force = -spring.stiffness * (*x - target) - spring.damping * *v;
+                *v += force / spring.mass.max(f32::EPSILON) * step;
+                *x += *v * step;
+            }
+            let settled = position.iter().zip(&target).zip(&self.velocity)
+                .all(|((x, target), v)| (x - target).abs() < SPRING_REST && v.abs() < SPRING_REST);
+            if settled {
+                self.value = to;
+                return Some(seconds - step * i as f32);
+            }
+        }
+        self.value = T::from_components(&position);
+        None
+    }
+}
+
+/// The edge of a container a sliding [`Transition`] enters from and leaves to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// How the children of [`Opt`](crate::layouts::Opt), [`EitherOr`](crate::layouts::EitherOr)
+/// and [`Enum`](crate::layouts::Enum) appear and disappear.
+///
+/// The effects can be combined, a child that is hidden is drawn with all of them applied fully
+/// and a child that is shown is drawn as is. Switching an [`Enum`](crate::layouts::Enum) or
+/// [`EitherOr`](crate::layouts::EitherOr) with a fade crossfades the outgoing and incoming children.
+///
+///```rust
+/// # use std::time::Duration;
+/// # use roost_ui::animation::{Transition, Edge, Easing};
+/// let transition = Transition::slide(Edge::Bottom, Duration::from_millis(250)).and_fade().easing(Easing::CubicOut);
+///```
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    pub duration: Duration,
+    pub easing: Easing,
+    /// Fades the child from transparent.
+    pub fade: bool,
+    /// Slides the child by its own size from the given edge.
+    pub slide: Option<Edge>,
+    /// Grows the child from the given scale around its center.
+    pub scale: Option<f32>,
+}
+
+impl Transition {
+    /// A transition without any effects, the child still stays drawn for `duration` after being hidden.
+    pub fn new(duration: Duration) -> Self {
+        Transition{duration, easing: Easing::QuadInOut, fade: false, slide: None, scale: None}
+    }
+
+    /// Fades the child in and out.
+    pub fn fade(duration: Duration) -> Self {Self::new(duration).and_fade()}
+
+    /// Slides the child in from and out to `edge`.
+    pub fn slide(edge: Edge, duration: Duration) -> Self {
+        Transition{slide: Some(edge), ..Self::new(duration)}
+    }
+
+    /// Grows the child in from and shrinks it out to `from`.
+    pub fn scale(from: f32, duration: Duration) -> Self {
+        Transition{scale: Some(from), ..Self::new(duration)}
+    }
+
+    /// Adds a fade to the transition.
+    pub fn and_fade(mut self) -> Self {
+        self.fade = true;
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 8] = [
+        Easing::Linear, Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut,
+        Easing::CubicIn, Easing::CubicOut, Easing::CubicInOut, Easing::BackOut,
+    ];
+
+    fn ms(ms: u64) -> Duration {Duration::from_millis(ms)}
+
+    fn close(a: f32, b: f32) -> bool {(a - b).abs() < 1e-4}
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in EASINGS {
+            assert!(close(easing.apply(0.0), 0.0), "{easing:?}");
+            assert!(close(easing.apply(1.0), 1.0), "{easing:?}");
+            assert!(close(easing.apply(-1.0), 0.0) && close(easing.apply(2.0), 1.0), "{easing:?}");
+        }
+        assert!(close(Easing::QuadIn.apply(0.5), 0.25));
+        assert!(close(Easing::CubicOut.apply(0.5), 0.875));
+        assert!(close(Easing::QuadInOut.apply(0.5), 0.5) && close(Easing::CubicInOut.apply(0.5), 0.5));
+        assert!(Easing::BackOut.apply(0.8) > 1.0);
+        assert!(close(Easing::Custom(|t| t.sqrt()).apply(0.25), 0.5));
+    }
+
+    #[test]
+    fn sequences_carry_the_leftover_time() {
+        let mut animation = Animation::new(0.0);
+        animation.tween(10.0, ms(100), Easing::Linear)
+            .then_wait(ms(100))
+            .then_tween(0.0, ms(100), Easing::Linear);
+        animation.advance(ms(50));
+        assert!(close(animation.value(), 5.0));
+        animation.advance(ms(100));
+        assert!(close(animation.value(), 10.0));
+        animation.advance(ms(100));
+        assert!(close(animation.value(), 5.0));
+        assert!(animation.advance(ms(100)));
+        assert!(close(animation.value(), 0.0));
+        assert!(!animation.is_running());
+        assert!(!animation.advance(ms(100)));
+    }
+
+    #[test]
+    fn springs_settle_on_the_target_and_pass_on_the_leftover_time() {
+        let mut animation = Animation::new(0.0);
+        animation.spring(1.0, Spring::STIFF).then_tween(2.0, Duration::from_secs(100), Easing::Linear);
+        animation.advance(ms(16));
+        assert!(animation.value() > 0.0 && animation.value() < 1.0);
+        animation.advance(Duration::from_secs(10));
+        assert!(animation.value() > 1.0 && animation.value() < 1.1);
+        assert!(animation.is_running());
+    }
+
+    #[test]
+    fn retargeting_a_spring_keeps_its_velocity() {
+        let mut animation = Animation::new(0.0);
+        animation.spring(100.0, Spring::default());
+        animation.advance(ms(50));
+        let value = animation.value();
+        animation.spring(0.0, Spring::default());
+        animation.advance(ms(1));
+        assert!(animation.value() > value);
+    }
+
+    #[test]
+    fn cancel_stops_and_finish_jumps_to_the_end() {
+        let mut animation = Animation::new(0.0);
+        animation.tween(1.0, ms(100), Easing::Linear).then_wait(ms(100));
+        animation.advance(ms(50));
+        animation.cancel();
+        assert!(!animation.is_running());
+        assert!(close(animation.value(), 0.5));
+
+        animation.tween(2.0, ms(100), Easing::Linear).then_tween(3.0, ms(100), Easing::Linear).then_wait(ms(100));
+        animation.finish();
+        assert!(!animation.is_running());
+        assert!(close(animation.value(), 3.0));
+    }
+}
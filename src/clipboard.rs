@@ -0,0 +1,128 @@
+use image::RgbaImage;
+
+/// A clipboard that text and images can be read from and written to,
+/// see [`Context::clipboard`](crate::Context::clipboard).
+///
+/// Implemented by [`SystemClipboard`] and [`MemoryClipboard`], and can be implemented
+/// to bridge other clipboards with [`Context::set_clipboard`](crate::Context::set_clipboard).
+pub trait Clipboard {
+    /// Returns the text on the clipboard, if any.
+    fn text(&mut self) -> Option<String>;
+    /// Replaces the contents of the clipboard with `text`.
+    fn set_text(&mut self, text: String);
+
+    /// Returns the image on the clipboard, if any.
+    fn image(&mut self) -> Option<RgbaImage> {None}
+    /// Replaces the contents of the clipboard with `image`, ignored if images are not supported.
+    fn set_image(&mut self, _image: RgbaImage) {}
+}
+
+/// A clipboard held in memory, used by headless contexts and on platforms without a system clipboard.
+///
+///```rust
+/// # use roost_ui::Context;
+/// let mut ctx = Context::headless(None);
+/// ctx.clipboard().set_text("hello".to_string());
+/// assert_eq!(ctx.clipboard().text(), Some("hello".to_string()));
+///```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+    image: Option<RgbaImage>,
+}
+
+impl Clipboard for MemoryClipboard {
+    fn text(&mut self) -> Option<String> {self.text.clone()}
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+        self.image = None;
+    }
+
+    fn image(&mut self) -> Option<RgbaImage> {self.image.clone()}
+    fn set_image(&mut self, image: RgbaImage) {
+        self.image = Some(image);
+        self.text = None;
+    }
+}
+
+/// The clipboard of the operating system.
+///
+/// Falls back to a [`MemoryClipboard`] when the system clipboard is unavailable,
+/// such as on mobile and web or without a display server.
+pub struct SystemClipboard(Backend);
+
+enum Backend {
+    #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+    System(arboard::Clipboard),
+    Memory(MemoryClipboard),
+}
+
+impl SystemClipboard {
+    pub fn new() -> Self {
+        #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+        match arboard::Clipboard::new() {
+            Ok(clipboard) => return SystemClipboard(Backend::System(clipboard)),
+            Err(e) => log::warn!("System clipboard unavailable, using an in-memory clipboard: {e}"),
+        }
+        SystemClipboard(Backend::Memory(MemoryClipboard::default()))
+    }
+}
+
+impl Default for SystemClipboard {
+    fn default() -> Self {Self::new()}
+}
+
+impl std::fmt::Debug for SystemClipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+            Backend::System(_) => write!(f, "SystemClipboard"),
+            Backend::Memory(memory) => f.debug_tuple("SystemClipboard").field(memory).finish(),
+        }
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn text(&mut self) -> Option<String> {
+        match &mut self.0 {
+            #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+            Backend::System(clipboard) => clipboard.get_text().ok(),
+            Backend::Memory(memory) => memory.text(),
+        }
+    }
+
+    fn set_text(&mut self, text: String) {
+        match &mut self.0 {
+            #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+            Backend::System(clipboard) => if let Err(e) = clipboard.set_text(text) {
+                log::warn!("Could not write to the clipboard: {e}");
+            },
+            Backend::Memory(memory) => memory.set_text(text),
+        }
+    }
+
+    fn image(&mut self) -> Option<RgbaImage> {
+        match &mut self.0 {
+            #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+            Backend::System(clipboard) => {
+                let image = clipboard.get_image().ok()?;
+                RgbaImage::from_raw(image.width as u32, image.height as u32, image.bytes.into_owned())
+            },
+            Backend::Memory(memory) => memory.image(),
+        }
+    }
+
+    fn set_image(&mut self, image: RgbaImage) {
+        match &mut self.0 {
+            #[cfg(not(any(target_os = "ios", target_os = "android", target_arch = "wasm32")))]
+            Backend::System(clipboard) => {
+                let (width, height) = image.dimensions();
+                let data = arboard::ImageData{width: width as usize, height: height as usize, bytes: image.into_raw().into()};
+                if let Err(e) = clipboard.set_image(data) {
+                    log::warn!("Could not write to the clipboard: {e}");
+                }
+            },
+            Backend::Memory(memory) => memory.set_image(image),
+        }
+    }
+}
@@ -6,7 +6,7 @@ use std::any::Any;
 
 use super::{Context, resources};
 use super::events::*;
-use super::layout::{SizeRequest, Area};
+use super::layout::{SizeRequest, Area, Scale, Scaling};
 use crate::events::OnEvent;
 
 pub use wgpu_canvas::{Text, Font, Span, Align, Cursor, Color};
@@ -15,9 +15,71 @@ pub use wgpu_canvas::Shape as ShapeType;
 #[derive(Default, Debug, Clone)]
 pub struct RequestBranch(pub SizeRequest, Vec<RequestBranch>);
 
+impl RequestBranch {
+    /// Returns the size requests of the children.
+    pub fn children(&self) -> &[RequestBranch] {&self.1}
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct SizedBranch(pub Size, Vec<(Offset, SizedBranch)>);
 
+impl SizedBranch {
+    /// Returns the offsets, relative to this branch, and the sized branches of the children.
+    pub fn children(&self) -> &[(Offset, SizedBranch)] {&self.1}
+}
+
+/// A node of a computed layout, pairing a drawable's name with its geometry.
+///
+/// Returned by [`measure`] and [`Headless::layout`](crate::headless::Headless::layout).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutNode {
+    /// The [`Drawable::name`] of the drawable.
+    pub name: String,
+    /// The offset relative to the parent node.
+    pub offset: Offset,
+    /// The allotted size.
+    pub size: Size,
+    pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    /// Returns this node and all of its descendants in depth first order,
+    /// each paired with its offset relative to this node's parent.
+    pub fn walk(&self) -> Vec<(Offset, &LayoutNode)> {
+        let mut nodes = vec![(self.offset, self)];
+        for child in &self.children {
+            nodes.extend(child.walk().into_iter().map(|(o, n)| ((self.offset.0+o.0, self.offset.1+o.1), n)));
+        }
+        nodes
+    }
+
+    /// Returns the first node in depth first order whose name contains `name`,
+    /// paired with its offset relative to this node's parent.
+    pub fn find(&self, name: &str) -> Option<(Offset, &LayoutNode)> {
+        self.walk().into_iter().find(|(_, n)| n.name.contains(name))
+    }
+}
+
+/// Runs the size request and build passes on `drawable` for the given logical `size`
+/// and returns the computed geometry tree.
+///
+/// A [`Context::headless`] can be used to measure drawables without a window.
+///
+///```rust
+/// # use roost_ui::Context;
+/// # use roost_ui::drawable::{measure, Shape, ShapeType, Color};
+/// # use roost_ui::layouts::{Bin, Row};
+/// let mut ctx = Context::headless(None);
+/// let mut row = Bin(Row::start(8.0), Shape::new(ShapeType::Rectangle(0.0, (10.0, 10.0), 0.0), Color(0, 0, 0, 255)));
+/// let tree = measure(&mut ctx, &mut row, (100.0, 100.0));
+/// assert_eq!(tree.children[0].size, (10.0, 10.0));
+///```
+pub fn measure(ctx: &mut Context, drawable: &mut dyn Drawable, size: Size) -> LayoutNode {
+    let request = _Drawable::request_size(&*drawable, ctx);
+    let sized = drawable.build(ctx, size, request);
+    drawable.layout_node((0.0, 0.0), &sized)
+}
+
 type Offset = (f32, f32);
 type Rect = (f32, f32, f32, f32);
 type Size = (f32, f32);
@@ -50,6 +112,13 @@ impl _Drawable for Box<dyn Drawable> {
     fn event(&mut self, ctx: &mut Context, sized: SizedBranch, event: Box<dyn Event>) {
         _Drawable::event(&mut **self, ctx, sized, event)
     }
+
+    fn layout_node(&self, offset: Offset, sized: &SizedBranch) -> LayoutNode {
+        _Drawable::layout_node(&**self, offset, sized)
+    }
+
+    fn hit_area(&self, size: Size) -> (Offset, Size) {_Drawable::hit_area(&**self, size)}
+    fn contains(&self, size: Size, position: Offset) -> bool {_Drawable::contains(&**self, size, position)}
 }
 
 impl<D: _Drawable + Debug + Any> Drawable for D {
@@ -71,6 +140,17 @@ pub(crate) trait _Drawable: Debug {
     fn name(&self) -> String {std::any::type_name_of_val(self).to_string()}
 
     fn event(&mut self, _ctx: &mut Context, _sized: SizedBranch, _event: Box<dyn Event>) {}
+
+    fn layout_node(&self, offset: Offset, sized: &SizedBranch) -> LayoutNode {
+        LayoutNode{name: self.name(), offset, size: sized.0, children: vec![]}
+    }
+
+    /// Returns the offset, relative to the drawable, and the size of the area that receives pointer input.
+    fn hit_area(&self, size: Size) -> (Offset, Size) {((0.0, 0.0), size)}
+
+    /// Returns `false` if `position`, relative to the hit area, is not drawn over,
+    /// letting it through to the drawables below.
+    fn contains(&self, _size: Size, _position: Offset) -> bool {true}
 }
 
 impl _Drawable for Text {
@@ -117,16 +197,276 @@ impl<D: _Drawable + Debug + Any> _Drawable for Option<D> {
             d.event(ctx, sized, event);
         }
     }
+
+    fn layout_node(&self, offset: Offset, sized: &SizedBranch) -> LayoutNode {
+        match self {
+            Some(d) => d.layout_node(offset, sized),
+            None => LayoutNode{name: "None".to_string(), offset, size: sized.0, children: vec![]},
+        }
+    }
+
+    fn hit_area(&self, size: Size) -> (Offset, Size) {
+        match self {
+            Some(d) => d.hit_area(size),
+            None => ((0.0, 0.0), size),
+        }
+    }
+
+    fn contains(&self, size: Size, position: Offset) -> bool {
+        self.as_ref().is_none_or(|d| d.contains(size, position))
+    }
+}
+
+
+/// Opacity, a translation, and a scale and rotation around `origin` applied to the items drawn by a subtree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Effect {
+    pub opacity: f32,
+    pub translate: Offset,
+    pub scale: f32,
+    /// Clockwise, in radians.
+    pub rotation: f32,
+    pub origin: Offset,
+}
+
+impl Default for Effect {
+    fn default() -> Self {Effect{opacity: 1.0, translate: (0.0, 0.0), scale: 1.0, rotation: 0.0, origin: (0.0, 0.0)}}
+}
+
+impl Effect {
+    /// Maps a point to where the effect draws it.
+    pub fn point(&self, (x, y): Offset) -> Offset {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (x, y) = ((x - self.origin.0) * self.scale, (y - self.origin.1) * self.scale);
+        (
+            self.origin.0 + x * cos - y * sin + self.translate.0,
+            self.origin.1 + x * sin + y * cos + self.translate.1,
+        )
+    }
+
+    /// Maps a point where the effect draws it back to the untransformed point.
+    pub fn invert(&self, (x, y): Offset) -> Offset {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (x, y) = (x - self.origin.0 - self.translate.0, y - self.origin.1 - self.translate.1);
+        let scale = match self.scale != 0.0 {
+            true => self.scale,
+            false => f32::EPSILON,
+        };
+        (
+            self.origin.0 + (x * cos + y * sin) / scale,
+            self.origin.1 + (y * cos - x * sin) / scale,
+        )
+    }
+
+    /// Returns the axis aligned bounds, as offset and size, of the rectangle at `offset` with `size` once transformed.
+    pub fn bounds(&self, offset: Offset, size: Size) -> (Offset, Size) {
+        Self::map_rect(offset, size, |p| self.point(p))
+    }
+
+    /// Returns the axis aligned bounds of the untransformed points drawn within the rectangle at `offset` with `size`.
+    pub fn inverse_bounds(&self, offset: Offset, size: Size) -> (Offset, Size) {
+        Self::map_rect(offset, size, |p| self.invert(p))
+    }
+
+    fn map_rect(offset: Offset, size: Size, map: impl Fn(Offset) -> Offset) -> (Offset, Size) {
+        let corners = [
+            (offset.0, offset.1), (offset.0 + size.0, offset.1),
+            (offset.0, offset.1 + size.1), (offset.0 + size.0, offset.1 + size.1),
+        ].map(map);
+        let min = corners.iter().fold((f32::MAX, f32::MAX), |m, c| (m.0.min(c.0), m.1.min(c.1)));
+        let max = corners.iter().fold((f32::MIN, f32::MIN), |m, c| (m.0.max(c.0), m.1.max(c.1)));
+        (min, (max.0 - min.0, max.1 - min.1))
+    }
+
+    fn fade(&self, color: Color) -> Color {
+        Color(color.0, color.1, color.2, (color.3 as f32 * self.opacity.clamp(0.0, 1.0)).round() as u8)
+    }
+
+    /// Moves a shape drawn at `offset` so its center follows the effect and turns it with the rotation.
+    fn shape(&self, offset: Offset, shape: ShapeType) -> (Offset, ShapeType) {
+        let size = shape.size();
+        let center = self.point((offset.0 + size.0 / 2.0, offset.1 + size.1 / 2.0));
+        let shape = match CanvasItem::scale_shape(shape, &Scale(self.scale as f64)) {
+            ShapeType::Ellipse(s, size, r) => ShapeType::Ellipse(s, size, r + self.rotation),
+            ShapeType::Rectangle(s, size, r) => ShapeType::Rectangle(s, size, r + self.rotation),
+            ShapeType::RoundedRectangle(s, size, c, r) => ShapeType::RoundedRectangle(s, size, c, r + self.rotation),
+        };
+        let size = shape.size();
+        ((center.0 - size.0 / 2.0, center.1 - size.1 / 2.0), shape)
+    }
+
+    /// Applies the effect to drawn items.
+    ///
+    /// Images without a recoloring keep their opacity, as the canvas has no way to fade them,
+    /// and text is moved by the rotation but not turned.
+    pub fn apply(&self, items: Vec<(CanvasArea, CanvasItem)>) -> Vec<(CanvasArea, CanvasItem)> {
+        if *self == Effect::default() { return items; }
+        items.into_iter().map(|(CanvasArea(offset, bound), item)| {
+            let bound = bound.map(|(x, y, w, h)| {
+                let ((x, y), (w, h)) = self.bounds((x, y), (w, h));
+                (x, y, w, h)
+            });
+            let (offset, item) = match item {
+                CanvasItem::Shape(shape, color) => {
+                    let (offset, shape) = self.shape(offset, shape);
+                    (offset, CanvasItem::Shape(shape, self.fade(color)))
+                },
+                CanvasItem::Image(shape, image, color) => {
+                    let (offset, shape) = self.shape(offset, shape);
+                    (offset, CanvasItem::Image(shape, image, color.map(|c| self.fade(c))))
+                },
+                CanvasItem::Text(mut text) => {
+                    text.scale *= self.scale;
+                    text.spans.iter_mut().for_each(|span| span.color = self.fade(span.color));
+                    (self.point(offset), CanvasItem::Text(text))
+                },
+            };
+            (CanvasArea(offset, bound), item)
+        }).collect()
+    }
+}
+
+/// A container that draws its child with an opacity, a translation, and a scale and rotation around `anchor`.
+///
+/// The child is laid out as if it was not transformed. Pointer input is mapped back through the transform,
+/// so the child receives it wherever it is drawn and with positions relative to its untransformed origin.
+///
+/// Pointer input only reaches the child where it is drawn, the corners of a rotated child's bounds
+/// go to the drawables below.
+///
+/// The canvas does not rotate text, so a child that draws text is drawn and hit-tested without the rotation,
+/// and a warning is logged. Images without a recoloring are not faded.
+///
+///```rust
+/// # use roost_ui::drawable::{Transform, Shape, ShapeType, Color};
+/// # let card = Shape::new(ShapeType::Rectangle(0.0, (100.0, 60.0), 8.0), Color(255, 255, 255, 255));
+/// let mut card = Transform::new(card);
+/// card.opacity = 0.5;
+/// card.rotation = std::f32::consts::FRAC_PI_8;
+///```
+#[derive(Debug)]
+pub struct Transform<D: Drawable + 'static> {
+    pub inner: D,
+    /// From 0.0, transparent, to 1.0, opaque.
+    pub opacity: f32,
+    pub translate: (f32, f32),
+    pub scale: f32,
+    /// Clockwise, in radians.
+    pub rotation: f32,
+    /// The point the child is scaled and rotated around, as a fraction of its size.
+    pub anchor: (f32, f32),
+    /// Set when the rotation was dropped because the child drew text in the last frame.
+    unrotated: bool,
+}
+
+impl<D: Drawable + 'static> Transform<D> {
+    /// Wraps `child` without transforming it, anchored at its center.
+    pub fn new(child: D) -> Self {
+        Transform{
+            inner: child, opacity: 1.0, translate: (0.0, 0.0), scale: 1.0, rotation: 0.0, anchor: (0.5, 0.5),
+            unrotated: false,
+        }
+    }
+
+    fn effect(&self, offset: Offset, size: Size) -> Effect {
+        Effect{
+            opacity: self.opacity,
+            translate: self.translate,
+            scale: self.scale,
+            rotation: if self.unrotated {0.0} else {self.rotation},
+            origin: (offset.0 + size.0 * self.anchor.0, offset.1 + size.1 * self.anchor.1),
+        }
+    }
+
+    fn draw_child(&mut self, child: SizedBranch, size: Size, offset: Offset, bound: Rect) -> Vec<(CanvasArea, CanvasItem)> {
+        let effect = self.effect(offset, size);
+        //The child is clipped to what ends up within the bound once transformed
+        let ((x, y), (w, h)) = effect.inverse_bounds((bound.0, bound.1), (bound.2, bound.3));
+        effect.apply(_Drawable::draw(&mut self.inner, child, offset, (x, y, w, h))).into_iter()
+            .map(|(CanvasArea(o, b), item)| (CanvasArea(o, b.map(|b| intersect(b, bound))), item)).collect()
+    }
 }
 
+impl<D: Drawable + 'static> _Drawable for Transform<D> {
+    fn request_size(&self, ctx: &mut Context) -> RequestBranch {
+        let request = _Drawable::request_size(&self.inner, ctx);
+        RequestBranch(request.0, vec![request])
+    }
+
+    fn build(&mut self, ctx: &mut Context, size: Size, request: RequestBranch) -> SizedBranch {
+        let size = request.0.get(size);
+        let request = request.children()[0].clone();
+        ctx.focus.enter(0, (0.0, 0.0));
+        let sized = _Drawable::build(&mut self.inner, ctx, size, request);
+        ctx.focus.exit();
+        SizedBranch(size, vec![((0.0, 0.0), sized)])
+    }
+
+    fn draw(&mut self, sized: SizedBranch, offset: Offset, bound: Rect) -> Vec<(CanvasArea, CanvasItem)> {
+        let Some((_, child)) = sized.children().first().cloned() else { return vec![] };
+        let items = self.draw_child(child.clone(), sized.0, offset, bound);
+        let unrotated = self.rotation != 0.0 && items.iter().any(|(_, item)| matches!(item, CanvasItem::Text(_)));
+        if unrotated == self.unrotated { return items; }
+        self.unrotated = unrotated;
+        if unrotated {
+            log::warn!("{} draws text, which can not be rotated, it is drawn without the rotation", _Drawable::name(&self.inner));
+        }
+        self.draw_child(child, sized.0, offset, bound)
+    }
+
+    fn event(&mut self, ctx: &mut Context, sized: SizedBranch, event: Box<dyn Event>) {
+        let Some((_, child)) = sized.children().first().cloned() else { return };
+        //Positions arrive relative to the hit area and leave relative to the untransformed child
+        let (hit, _) = self.hit_area(sized.0);
+        let effect = self.effect((0.0, 0.0), sized.0);
+        let event = map_position(event, |p| effect.invert((p.0 + hit.0, p.1 + hit.1)));
+        if let Some(Some(event)) = event.pass(ctx, &vec![((0.0, 0.0), sized.0)]).pop() {
+            ctx.focus.enter(0, (0.0, 0.0));
+            _Drawable::event(&mut self.inner, ctx, child, event);
+            ctx.focus.exit();
+        }
+    }
+
+    fn layout_node(&self, offset: Offset, sized: &SizedBranch) -> LayoutNode {
+        LayoutNode{
+            name: _Drawable::name(self),
+            offset,
+            size: sized.0,
+            children: sized.children().iter().map(|(o, branch)| _Drawable::layout_node(&self.inner, *o, branch)).collect(),
+        }
+    }
+
+    fn hit_area(&self, size: Size) -> (Offset, Size) {
+        self.effect((0.0, 0.0), size).bounds((0.0, 0.0), size)
+    }
 
-/// A basic drawable shape with a fill color.
+    fn contains(&self, size: Size, position: Offset) -> bool {
+        let (hit, _) = self.hit_area(size);
+        let (x, y) = self.effect((0.0, 0.0), size).invert((position.0 + hit.0, position.1 + hit.1));
+        (0.0..=size.0).contains(&x) && (0.0..=size.1).contains(&y)
+    }
+}
+
+/// A basic drawable shape with a fill color and an optional border.
 #[derive(Clone, Copy, Debug)]
 pub struct Shape {
     /// The geometric form of the shape (e.g., rectangle, circle).
     pub shape: ShapeType,
     /// The fill or stroke color of the shape.
     pub color: Color,
+    /// A border drawn over the edge of the shape.
+    pub border: Option<Border>,
+}
+
+impl Shape {
+    pub fn new(shape: ShapeType, color: Color) -> Self {
+        Shape{shape, color, border: None}
+    }
+
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
 }
 
 impl _Drawable for Shape {
@@ -136,8 +476,300 @@ impl _Drawable for Shape {
 
     fn draw(&mut self, _sized: SizedBranch, offset: Offset, bound: Rect) -> Vec<(CanvasArea, CanvasItem)> {
         //TODO: use sized.0 as the size of the shape?
-        vec![(CanvasArea(offset, Some(bound)), CanvasItem::Shape(self.shape, self.color))]
+        let mut items = vec![(CanvasArea(offset, Some(bound)), CanvasItem::Shape(self.shape, self.color))];
+        if let Some(border) = self.border {
+            items.extend(border.items(self.shape).into_iter().map(|(o, shape, clip)| {
+                let clip = clip.map_or(bound, |(x, y, w, h)| intersect((offset.0 + x, offset.1 + y, w, h), bound));
+                (CanvasArea((offset.0 + o.0, offset.1 + o.1), Some(clip)), CanvasItem::Shape(shape, border.color))
+            }));
+        }
+        items
+    }
+}
+
+/// The width of each side of a [`Border`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BorderWidths {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl BorderWidths {
+    /// The same width on every side.
+    pub fn all(width: f32) -> Self {
+        BorderWidths{top: width, right: width, bottom: width, left: width}
+    }
+
+    fn max(&self) -> f32 {
+        self.top.max(self.right).max(self.bottom).max(self.left)
+    }
+
+    fn is_uniform(&self) -> bool {
+        self.top == self.right && self.right == self.bottom && self.bottom == self.left
+    }
+}
+
+/// The pattern a [`Border`] is drawn with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StrokeStyle {
+    #[default]
+    Solid,
+    /// Dashes `dash` long, separated by `gap`.
+    Dashed{dash: f32, gap: f32},
+    /// Round dots as wide as the border, separated by `gap`.
+    Dotted{gap: f32},
+}
+
+/// Where a [`Border`] is drawn relative to the edge of its shape.
+///
+/// Center and outer borders extend past the size of the shape,
+/// so they do not affect its layout but can be clipped by its container.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StrokeAlign {
+    /// Within the shape.
+    #[default]
+    Inner,
+    /// Centered on the edge.
+    Center,
+    /// Around the shape.
+    Outer,
+}
+
+/// The border of a [`Shape`], drawn in its own color over the fill.
+///
+/// Rectangles can have a different width on each side. Rounded rectangles and ellipses
+/// use the widest side all around. The canvas cannot bend dashes, so each dash along a curve is
+/// the whole outline clipped to the bounds of the dash, which cuts the dashes on tight curves square.
+///
+///```rust
+/// # use roost_ui::drawable::{Shape, ShapeType, Color, Border, StrokeAlign};
+/// let card = Shape::new(ShapeType::RoundedRectangle(0.0, (200.0, 48.0), 8.0, 0.0), Color(255, 255, 255, 255))
+///     .border(Border::new(1.0, Color(0, 0, 0, 255)).dashed(4.0, 2.0).align(StrokeAlign::Outer));
+///```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Border {
+    pub color: Color,
+    pub widths: BorderWidths,
+    pub style: StrokeStyle,
+    pub align: StrokeAlign,
+}
+
+impl Border {
+    /// A solid inner border with the same width on every side.
+    pub fn new(width: f32, color: Color) -> Self {
+        Border{color, widths: BorderWidths::all(width), style: StrokeStyle::Solid, align: StrokeAlign::Inner}
+    }
+
+    /// A solid inner border with a width for each side.
+    pub fn sides(widths: BorderWidths, color: Color) -> Self {
+        Border{widths, ..Self::new(0.0, color)}
+    }
+
+    pub fn dashed(mut self, dash: f32, gap: f32) -> Self {
+        self.style = StrokeStyle::Dashed{dash, gap};
+        self
+    }
+
+    pub fn dotted(mut self, gap: f32) -> Self {
+        self.style = StrokeStyle::Dotted{gap};
+        self
+    }
+
+    pub fn align(mut self, align: StrokeAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Returns how far a side `width` wide extends outside of the shape.
+    fn outset(&self, width: f32) -> f32 {
+        match self.align {
+            StrokeAlign::Inner => 0.0,
+            StrokeAlign::Center => width / 2.0,
+            StrokeAlign::Outer => width,
+        }
+    }
+
+    /// Returns the shapes making up the border of `shape`, with their offsets from the shape
+    /// and the area they are clipped to, if any.
+    fn items(&self, shape: ShapeType) -> Vec<(Offset, ShapeType, Option<Rect>)> {
+        let (w, h) = shape.size();
+        let unclipped = |items: Vec<(Offset, ShapeType)>| items.into_iter().map(|(o, shape)| (o, shape, None)).collect();
+        match shape {
+            ShapeType::Rectangle(..) if self.widths.is_uniform() && self.style == StrokeStyle::Solid =>
+                unclipped(self.outline(shape, self.widths.top)),
+            ShapeType::Rectangle(..) => unclipped(self.sides_of((w, h))),
+            _ => {
+                let width = self.widths.max();
+                match self.style {
+                    StrokeStyle::Solid => unclipped(self.outline(shape, width)),
+                    StrokeStyle::Dashed{dash, gap} => self.dashes_along(shape, width, dash, gap),
+                    StrokeStyle::Dotted{gap} => unclipped(self.dots_along(shape, width, gap)),
+                }
+            }
+        }
+    }
+
+    /// A single shape stroked with `width`, grown to the alignment of the border.
+    fn outline(&self, shape: ShapeType, width: f32) -> Vec<(Offset, ShapeType)> {
+        if width <= 0.0 { return vec![]; }
+        let out = self.outset(width);
+        let (w, h) = shape.size();
+        let size = (w + out * 2.0, h + out * 2.0);
+        let shape = match shape {
+            ShapeType::Ellipse(_, _, r) => ShapeType::Ellipse(width, size, r),
+            ShapeType::Rectangle(_, _, r) => ShapeType::Rectangle(width, size, r),
+            ShapeType::RoundedRectangle(_, _, c, r) => ShapeType::RoundedRectangle(width, size, c + out, r),
+        };
+        vec![((-out, -out), shape)]
+    }
+
+    /// A rectangle for each side of a rectangular border, or its dashes and dots.
+    fn sides_of(&self, (w, h): Size) -> Vec<(Offset, ShapeType)> {
+        let BorderWidths{top, right, bottom, left} = self.widths;
+        let (x, y) = (-self.outset(left), -self.outset(top));
+        let (x2, y2) = (w + self.outset(right), h + self.outset(bottom));
+        //The top and bottom sides cover the corners
+        let sides = [
+            ((x, y), (x2 - x, 0.0), top),
+            ((x, y2 - bottom), (x2 - x, 0.0), bottom),
+            ((x, y + top), (0.0, y2 - bottom - y - top), left),
+            ((x2 - right, y + top), (0.0, y2 - bottom - y - top), right),
+        ];
+        sides.into_iter().filter(|(.., width)| *width > 0.0).flat_map(|((sx, sy), (dx, dy), width)| {
+            let length = dx.max(dy).max(0.0);
+            let horizontal = dx > 0.0;
+            let rect = move |from: f32, to: f32| match horizontal {
+                true => ((sx + from, sy), ShapeType::Rectangle(0.0, (to - from, width), 0.0)),
+                false => ((sx, sy + from), ShapeType::Rectangle(0.0, (width, to - from), 0.0)),
+            };
+            match self.style {
+                StrokeStyle::Solid => vec![rect(0.0, length)],
+                StrokeStyle::Dashed{dash, gap} => Self::dashes(length, dash, gap).into_iter()
+                    .map(|(from, to)| rect(from, to)).collect(),
+                StrokeStyle::Dotted{gap} => Self::dots(length, width, gap).into_iter().map(|from| {
+                    let dot = ShapeType::Ellipse(0.0, (width, width), 0.0);
+                    match horizontal {
+                        true => ((sx + from, sy), dot),
+                        false => ((sx, sy + from), dot),
+                    }
+                }).collect(),
+            }
+        }).collect()
+    }
+
+    /// The start and end of each dash along a line `length` long, none unless every length is finite.
+    fn dashes(length: f32, dash: f32, gap: f32) -> Vec<(f32, f32)> {
+        if ![length, dash, gap].iter().all(|l| l.is_finite()) || dash <= 0.0 { return vec![]; }
+        let period = (dash + gap).max(0.5);
+        (0..(length / period).ceil().max(0.0) as usize).map(|i| i as f32 * period)
+            .map(|from| (from, (from + dash).min(length))).collect()
+    }
+
+    /// The start of each dot `width` wide that fits along a line `length` long.
+    fn dots(length: f32, width: f32, gap: f32) -> Vec<f32> {
+        if !length.is_finite() || !width.is_finite() || length < width { return vec![]; }
+        let period = (width + gap.max(0.0)).max(0.5);
+        (0..=((length - width) / period) as usize).map(|i| i as f32 * period).collect()
+    }
+
+    /// The middle line of a curved border, inset by half its width from its outer edge,
+    /// with the length of each of its segments and the inset.
+    fn middle_path(&self, shape: ShapeType, width: f32) -> (Vec<Offset>, Vec<f32>, f32) {
+        let (w, h) = shape.size();
+        let inset = width / 2.0 - self.outset(width);
+        let (w, h) = ((w - inset * 2.0).max(0.0), (h - inset * 2.0).max(0.0));
+        let path = match shape {
+            ShapeType::RoundedRectangle(_, _, c, _) => rounded_path((w, h), (c - inset).max(0.0)),
+            _ => ellipse_path((w, h)),
+        };
+        let lengths = path.iter().zip(path.iter().cycle().skip(1)).map(|(a, b)| (b.0 - a.0).hypot(b.1 - a.1)).collect();
+        (path, lengths, inset)
+    }
+
+    /// The outline of a curved border once per dash, clipped to the bounds of the dash.
+    fn dashes_along(&self, shape: ShapeType, width: f32, dash: f32, gap: f32) -> Vec<(Offset, ShapeType, Option<Rect>)> {
+        let Some((offset, outline)) = self.outline(shape, width).pop() else { return vec![] };
+        let (path, lengths, inset) = self.middle_path(shape, width);
+        let ends = lengths.iter().scan(0.0, |end, length| {
+            *end += length;
+            Some(*end)
+        }).collect::<Vec<f32>>();
+        Self::dashes(ends.last().copied().unwrap_or_default(), dash, gap).into_iter().map(|(from, to)| {
+            //The dash spans its ends and the corners of the path between them, each pushed out to both edges
+            let corners = ends.iter().filter(|end| **end > from && **end < to).flat_map(|end| [end - 1e-3, end + 1e-3]);
+            let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+            for distance in [from, to].into_iter().chain(corners) {
+                let ((x, y), (nx, ny)) = along(&path, &lengths, distance);
+                for side in [-width / 2.0, width / 2.0] {
+                    let (x, y) = (x + inset + nx * side, y + inset + ny * side);
+                    (min, max) = ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)));
+                }
+            }
+            (offset, outline, Some((min.0, min.1, max.0 - min.0, max.1 - min.1)))
+        }).collect()
+    }
+
+    /// Dots `width` wide spread evenly along the middle of a curved border.
+    fn dots_along(&self, shape: ShapeType, width: f32, gap: f32) -> Vec<(Offset, ShapeType)> {
+        if width <= 0.0 { return vec![]; }
+        let (path, lengths, inset) = self.middle_path(shape, width);
+        let perimeter = lengths.iter().sum::<f32>();
+        //Spread the dots evenly so the last one does not run into the first
+        let count = (perimeter / (width + gap.max(0.0)).max(0.5)).floor().max(1.0) as usize;
+        (0..count).map(|i| {
+            let ((x, y), _) = along(&path, &lengths, i as f32 * perimeter / count as f32);
+            ((x + inset - width / 2.0, y + inset - width / 2.0), ShapeType::Ellipse(0.0, (width, width), 0.0))
+        }).collect()
+    }
+}
+
+/// The number of segments each quarter of a curve is split into when placing dots along it.
+const CURVE_SEGMENTS: usize = 16;
+
+/// Points along the edge of a rectangle of `size` with corners rounded by `radius`, clockwise.
+fn rounded_path(size: Size, radius: f32) -> Vec<Offset> {
+    let r = radius.min(size.0 / 2.0).min(size.1 / 2.0);
+    let centers = [(size.0 - r, r), (size.0 - r, size.1 - r), (r, size.1 - r), (r, r)];
+    centers.into_iter().enumerate().flat_map(|(corner, (cx, cy))| (0..=CURVE_SEGMENTS).map(move |i| {
+        let angle = (corner as f32 - 1.0 + i as f32 / CURVE_SEGMENTS as f32) * std::f32::consts::FRAC_PI_2;
+        (cx + r * angle.cos(), cy + r * angle.sin())
+    })).collect()
+}
+
+/// Points along the edge of an ellipse filling `size`, clockwise.
+fn ellipse_path(size: Size) -> Vec<Offset> {
+    let (rx, ry) = (size.0 / 2.0, size.1 / 2.0);
+    (0..CURVE_SEGMENTS * 4).map(|i| {
+        let angle = i as f32 / (CURVE_SEGMENTS * 4) as f32 * std::f32::consts::TAU;
+        (rx + rx * angle.cos(), ry + ry * angle.sin())
+    }).collect()
+}
+
+/// Returns the point `distance` along the closed `path`, whose segments are `lengths` long,
+/// and the unit normal of the segment it is on.
+fn along(path: &[Offset], lengths: &[f32], mut distance: f32) -> (Offset, Offset) {
+    for (i, length) in lengths.iter().enumerate() {
+        if distance <= *length {
+            let (a, b) = (path[i], path[(i + 1) % path.len()]);
+            let t = if *length > 0.0 {distance / length} else {0.0};
+            let normal = match *length > 0.0 {
+                true => (-(b.1 - a.1) / length, (b.0 - a.0) / length),
+                false => (0.0, 0.0),
+            };
+            return ((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t), normal);
+        }
+        distance -= length;
     }
+    (path.first().copied().unwrap_or_default(), (0.0, 0.0))
+}
+
+/// Returns the overlap of two rectangles, empty if they do not overlap.
+fn intersect(a: Rect, b: Rect) -> Rect {
+    let (x, y) = (a.0.max(b.0), a.1.max(b.1));
+    let (x2, y2) = ((a.0 + a.2).min(b.0 + b.2), (a.1 + a.3).min(b.1 + b.3));
+    (x, y, (x2 - x).max(0.0), (y2 - y).max(0.0))
 }
 
 /// A drawable image.
@@ -184,18 +816,27 @@ impl<C: Component + ?Sized + 'static + OnEvent> _Drawable for C {
         let requests = self.children().into_iter().map(|i| _Drawable::request_size(i, ctx)).collect::<Vec<_>>();
         let info = requests.iter().map(|i| i.0).collect::<Vec<_>>();
         let r = Component::request_size(self, ctx, info);
-        RequestBranch(r, requests)
+        RequestBranch(ctx.check_request(|| _Drawable::name(self), r), requests)
     }
 
     fn build(&mut self, ctx: &mut Context, size: Size, request: RequestBranch) -> SizedBranch {
+        if OnEvent::hoverable(self) {
+            ctx.keep_hover();
+        }
         let size = request.0.get(size);
         let children = request.1.iter().map(|b| b.0).collect::<Vec<_>>();
+        let areas = Component::build(self, ctx, size, children).into_iter()
+            .map(|area| ctx.check_area(|| _Drawable::name(self), area))
+            .collect::<Vec<_>>();
         SizedBranch(
             size,
-            Component::build(self, ctx, size, children).into_iter()
-            .zip(self.children_mut()).zip(request.1)
-            .map(|((Area{offset, size}, child), branch)| {
-                (offset, child.build(ctx, size, branch))
+            areas.into_iter()
+            .zip(self.children_mut()).zip(request.1).enumerate()
+            .map(|(i, ((Area{offset, size}, child), branch))| {
+                ctx.focus.enter(i, offset);
+                let sized = child.build(ctx, size, branch);
+                ctx.focus.exit();
+                (offset, sized)
             }).collect()
         )
     }
@@ -217,11 +858,55 @@ impl<C: Component + ?Sized + 'static + OnEvent> _Drawable for C {
     }
 
     fn event(&mut self, ctx: &mut Context, sized: SizedBranch, event: Box<dyn Event>) {
-        let children = sized.1.iter().map(|(o, branch)| (*o, branch.0)).collect::<Vec<_>>();
-        for event in OnEvent::on_event(self, ctx, event) {
-            event.pass(ctx, &children).into_iter().zip(self.children_mut()).zip(sized.1.iter()).for_each(
-                |((e, child), branch)| if let Some(e) = e {child.event(ctx, branch.1.clone(), e);}
+        let pending = std::mem::take(&mut ctx.bubbling);
+        let areas = sized.1.iter().zip(self.children()).map(|((o, branch), child)| {
+            let (hit, size) = child.hit_area(branch.0);
+            ((o.0+hit.0, o.1+hit.1), size)
+        }).collect::<Vec<_>>();
+        let hover = OnEvent::hoverable(self).then(|| ctx.hover_transition(&*event)).flatten();
+        let mut events = match hover {
+            Some(hover) => OnEvent::on_event(self, ctx, hover),
+            None => Vec::new(),
+        };
+        events.extend(OnEvent::on_event(self, ctx, event));
+        //Events emitted by this component start bubbling at its parent
+        let mut bubbled = std::mem::take(&mut ctx.bubbling);
+        for event in events {
+            //Children not drawn under the position get no size, letting it through to the ones below
+            let children = match pointer_position(&*event) {
+                Some(p) => areas.iter().zip(self.children()).zip(&sized.1).map(|((&(offset, size), child), (_, branch))|
+                    match child.contains(branch.0, (p.0 - offset.0, p.1 - offset.1)) {
+                        true => (offset, size),
+                        false => (offset, (0.0, 0.0)),
+                    }
+                ).collect(),
+                None => areas.clone(),
+            };
+            let mut from_children = Vec::new();
+            event.pass(ctx, &children).into_iter().zip(self.children_mut()).zip(sized.1.iter()).enumerate().for_each(
+                |(i, ((e, child), branch))| if let Some(e) = e {
+                    ctx.focus.enter(i, branch.0);
+                    child.event(ctx, branch.1.clone(), e);
+                    ctx.focus.exit();
+                    from_children.append(&mut ctx.bubbling);
+                }
             );
+            for event in from_children {
+                let event = OnEvent::on_bubble(self, ctx, event);
+                bubbled.append(&mut ctx.bubbling);
+                bubbled.extend(event);
+            }
+        }
+        ctx.bubbling = pending;
+        ctx.bubbling.extend(bubbled);
+    }
+
+    fn layout_node(&self, offset: Offset, sized: &SizedBranch) -> LayoutNode {
+        LayoutNode{
+            name: _Drawable::name(self),
+            offset,
+            size: sized.0,
+            children: sized.1.iter().zip(self.children()).map(|((o, branch), child)| child.layout_node(*o, branch)).collect()
         }
     }
 }
@@ -236,3 +921,154 @@ macro_rules! drawables {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{Headless, Probe};
+    use crate::layouts::Stack;
+    use std::f32::consts::FRAC_PI_4;
+    use std::sync::{Arc, Mutex};
+
+    fn close(a: Offset, b: Offset) -> bool {(a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3}
+
+    fn square() -> Shape {Shape::new(ShapeType::Rectangle(0.0, (100.0, 100.0), 0.0), Color(0, 0, 0, 255))}
+
+    #[test]
+    fn effect_invert_undoes_point() {
+        let effect = Effect{opacity: 1.0, translate: (12.0, -7.0), scale: 1.5, rotation: 0.6, origin: (40.0, 30.0)};
+        for p in [(0.0, 0.0), (40.0, 30.0), (-25.0, 90.0), (300.0, 12.5)] {
+            assert!(close(effect.invert(effect.point(p)), p));
+            assert!(close(effect.point(effect.invert(p)), p));
+        }
+        assert!(close(effect.point((40.0, 30.0)), (52.0, 23.0)));
+    }
+
+    #[test]
+    fn rotated_transforms_only_contain_what_they_draw() {
+        let mut transform = Transform::new(square());
+        transform.rotation = FRAC_PI_4;
+        let (hit, size) = transform.hit_area((100.0, 100.0));
+        assert!(size.0 > 140.0 && size.0 < 142.0);
+        let at = |p: Offset| (p.0 - hit.0, p.1 - hit.1);
+        assert!(transform.contains((100.0, 100.0), at((50.0, 50.0))));
+        assert!(transform.contains((100.0, 100.0), at((50.0, -15.0))));
+        assert!(!transform.contains((100.0, 100.0), at((5.0, 5.0))));
+    }
+
+    #[test]
+    fn dashes_fill_the_length_and_stop_on_non_finite_lengths() {
+        assert_eq!(Border::dashes(10.0, 3.0, 1.0), vec![(0.0, 3.0), (4.0, 7.0), (8.0, 10.0)]);
+        assert_eq!(Border::dashes(8.0, 3.0, 1.0).len(), 2);
+        assert!(Border::dashes(f32::INFINITY, 3.0, 1.0).is_empty());
+        assert!(Border::dashes(f32::NAN, 3.0, 1.0).is_empty());
+        assert!(Border::dashes(10.0, 0.0, 1.0).is_empty());
+        assert_eq!(Border::dashes(1.0, 0.1, 0.0).len(), 2);
+    }
+
+    #[test]
+    fn dots_fit_within_the_length() {
+        assert_eq!(Border::dots(10.0, 2.0, 2.0), vec![0.0, 4.0, 8.0]);
+        assert_eq!(Border::dots(9.0, 2.0, 2.0), vec![0.0, 4.0]);
+        assert!(Border::dots(1.0, 2.0, 2.0).is_empty());
+        assert!(Border::dots(f32::INFINITY, 2.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn curved_dashes_clip_the_outline_to_each_dash() {
+        let shape = ShapeType::RoundedRectangle(0.0, (100.0, 40.0), 10.0, 0.0);
+        let items = Border::new(2.0, Color(0, 0, 0, 255)).dashed(10.0, 10.0).items(shape);
+        assert!(items.len() > 5);
+        assert!(items.iter().all(|(_, item, _)| matches!(item, ShapeType::RoundedRectangle(2.0, (100.0, 40.0), ..))));
+        //The path starts where the top right corner begins, the second dash is on the straight right edge
+        let (x, _, w, h) = items[1].2.unwrap();
+        assert!(close((x, w), (98.0, 2.0)) && close((h, 0.0), (10.0, 0.0)));
+        let clips = items.iter().map(|(.., clip)| clip.unwrap()).collect::<Vec<_>>();
+        assert!(clips.iter().all(|(x, y, w, h)| *x >= -1e-3 && *y >= -1e-3 && x + w <= 100.001 && y + h <= 40.001));
+    }
+
+    #[derive(Debug)]
+    struct Layered(Stack, Probe<Shape>, Transform<Probe<Shape>>);
+    impl OnEvent for Layered {}
+
+    impl Component for Layered {
+        fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![&mut self.1, &mut self.2]}
+        fn children(&self) -> Vec<&dyn Drawable> {vec![&self.1, &self.2]}
+        fn request_size(&self, ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {
+            crate::layout::Layout::request_size(&self.0, ctx, children)
+        }
+        fn build(&mut self, ctx: &mut Context, size: Size, children: Vec<SizeRequest>) -> Vec<Area> {
+            crate::layout::Layout::build(&self.0, ctx, size, children)
+        }
+    }
+
+    #[test]
+    fn pointer_input_falls_through_the_corners_of_rotated_children() {
+        let (below, above) = (Probe::new(square()), Probe::new(square()));
+        let (below_log, above_log) = (below.log(), above.log());
+        let mut transform = Transform::new(above);
+        transform.rotation = FRAC_PI_4;
+        let mut app = Headless::with_root(Context::headless(None), Layered(Stack::default(), below, transform), (200, 200), 1.0);
+
+        app.click((5.0, 5.0));
+        app.frame();
+        assert!(below_log.contains("Some((5.0, 5.0))"));
+        assert!(!above_log.contains("Some("));
+
+        below_log.clear();
+        app.click((50.0, 50.0));
+        app.frame();
+        assert!(above_log.contains("Some((50.0, 50.0))"));
+        assert!(!below_log.contains("Some("));
+    }
+
+    /// Logs the events bubbled up to it under its name, and stops them when `stop` is set.
+    #[derive(Debug)]
+    struct Ancestor(Stack, Box<dyn Drawable>, &'static str, bool, Arc<Mutex<Vec<String>>>);
+
+    impl OnEvent for Ancestor {
+        fn on_bubble(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Option<Box<dyn Event>> {
+            self.4.lock().unwrap().push(format!("{}: {event:?}", self.2));
+            (!self.3).then_some(event)
+        }
+    }
+
+    impl Component for Ancestor {
+        fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![&mut self.1]}
+        fn children(&self) -> Vec<&dyn Drawable> {vec![&self.1]}
+        fn request_size(&self, ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {
+            crate::layout::Layout::request_size(&self.0, ctx, children)
+        }
+        fn build(&mut self, ctx: &mut Context, size: Size, children: Vec<SizeRequest>) -> Vec<Area> {
+            crate::layout::Layout::build(&self.0, ctx, size, children)
+        }
+    }
+
+    fn bubbled(stop_in_middle: bool) -> (Vec<String>, crate::headless::ProbeLog) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let probe = Probe::new(square());
+        let probe_log = probe.log();
+        let button = crate::emitters::Button::new(probe);
+        let middle = Ancestor(Stack::default(), Box::new(button), "middle", stop_in_middle, log.clone());
+        let outer = Ancestor(Stack::default(), Box::new(middle), "outer", false, log.clone());
+        let mut app = Headless::with_root(Context::headless(None), outer, (200, 200), 1.0);
+        app.click((10.0, 10.0));
+        app.frame();
+        let log = log.lock().unwrap().clone();
+        (log, probe_log)
+    }
+
+    #[test]
+    fn bubbled_events_reach_each_ancestor_in_order() {
+        let (log, probe_log) = bubbled(false);
+        assert_eq!(log, vec!["middle: Pressed(true)", "outer: Pressed(true)"]);
+        //Bubbled events go up, not down to the children of the emitter
+        assert_eq!(probe_log.count("Pressed(true)"), 1);
+    }
+
+    #[test]
+    fn bubbling_stops_when_an_ancestor_returns_none() {
+        let (log, _) = bubbled(true);
+        assert_eq!(log, vec!["middle: Pressed(true)"]);
+    }
+}
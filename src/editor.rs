@@ -0,0 +1,596 @@
+use std::ops::Range;
+
+use crate::events::{Key, KeyboardEvent, Modifiers, NamedKey};
+
+const UNDO_LIMIT: usize = 100;
+
+/// Restricts which characters can be entered into a [`TextEditor`].
+#[derive(Debug, Clone, Copy, Default)]
+pub enum InputFilter {
+    /// Every character is accepted.
+    #[default]
+    Any,
+    /// Only ASCII digits are accepted.
+    Numeric,
+    /// ASCII digits, a single decimal point and a leading minus sign are accepted.
+    Decimal,
+    /// Characters for which the function returns `true` are accepted.
+    Custom(fn(char) -> bool),
+}
+
+impl InputFilter {
+    /// Returns `true` if `c` may be inserted at char index `at` of `text`.
+    fn accepts(&self, c: char, text: &str, at: usize) -> bool {
+        match self {
+            InputFilter::Any => true,
+            InputFilter::Numeric => c.is_ascii_digit(),
+            InputFilter::Decimal => c.is_ascii_digit()
+                || (c == '.' && !text.contains('.'))
+                || (c == '-' && at == 0 && !text.starts_with('-')),
+            InputFilter::Custom(filter) => filter(c),
+        }
+    }
+}
+
+/// A caret movement, see [`TextEditor::move_caret`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    Start,
+    End,
+}
+
+/// A snapshot of a [`TextEditor`] for drawing it.
+///
+/// Indices are in chars and refer to the displayed text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextState {
+    /// The text to display, masked for password inputs, with the composition inserted at the caret.
+    pub text: String,
+    pub caret: usize,
+    pub selection: Option<Range<usize>>,
+    /// The text being composed with an input method, drawn underlined.
+    pub composition: Option<Range<usize>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Typing,
+    Deleting,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    caret: usize,
+    anchor: Option<usize>,
+}
+
+/// The editing model of a text input.
+///
+/// Holds the text, the caret and the selection, and applies insertions, deletions
+/// and caret movements with undo and redo history. Indices are in chars.
+/// Used by [`TextInput`](crate::emitters::TextInput), which feeds it keyboard and mouse input.
+///
+///```rust
+/// # use roost_ui::editor::{TextEditor, Motion};
+/// let mut editor = TextEditor::new(false);
+/// editor.insert("hello world");
+/// editor.move_caret(Motion::WordLeft, true);
+/// assert_eq!(editor.selected_text(), Some("world".to_string()));
+///```
+#[derive(Debug, Clone, Default)]
+pub struct TextEditor {
+    text: String,
+    caret: usize,
+    anchor: Option<usize>,
+    multiline: bool,
+    max_length: Option<usize>,
+    filter: InputFilter,
+    mask: Option<char>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: Option<Edit>,
+    preedit: Option<(String, Option<(usize, usize)>)>,
+}
+
+impl TextEditor {
+    /// Creates an empty editor, `multiline` allows line breaks.
+    pub fn new(multiline: bool) -> Self {
+        TextEditor{multiline, ..Default::default()}
+    }
+
+    /// Returns a mutable reference to the maximum number of chars, `None` for no limit.
+    pub fn max_length(&mut self) -> &mut Option<usize> {&mut self.max_length}
+    /// Returns a mutable reference to the [`InputFilter`].
+    pub fn filter(&mut self) -> &mut InputFilter {&mut self.filter}
+    /// Returns a mutable reference to the char displayed in place of every char, `Some('•')` for passwords.
+    ///
+    /// While masked, word motions move to the start or end of the text so they do not reveal the word boundaries.
+    pub fn mask(&mut self) -> &mut Option<char> {&mut self.mask}
+    /// Returns `true` if line breaks are allowed.
+    pub fn is_multiline(&self) -> bool {self.multiline}
+
+    /// Returns the text.
+    pub fn text(&self) -> &str {&self.text}
+
+    /// Replaces the text, moving the caret to the end and clearing the history.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.chars().filter(|c| self.multiline || *c != '\n').collect();
+        self.caret = self.len();
+        self.anchor = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = None;
+        self.preedit = None;
+    }
+
+    /// Returns the caret position.
+    pub fn caret(&self) -> usize {self.caret}
+
+    /// Returns the selected range, if the selection is not empty.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.anchor.filter(|anchor| *anchor != self.caret)
+            .map(|anchor| anchor.min(self.caret)..anchor.max(self.caret))
+    }
+
+    /// Returns the selected text.
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection().map(|range| self.text.chars().skip(range.start).take(range.len()).collect())
+    }
+
+    /// Returns the selected text to copy, `None` while masked so passwords can not be copied.
+    pub fn copy(&self) -> Option<String> {
+        self.selected_text().filter(|_| self.mask.is_none())
+    }
+
+    /// Removes and returns the selected text, `None` while masked.
+    pub fn cut(&mut self) -> Option<String> {
+        let text = self.copy()?;
+        self.delete(Motion::Left);
+        Some(text)
+    }
+
+    /// Returns the text to display, masked for password inputs.
+    pub fn display_text(&self) -> String {
+        match self.mask {
+            Some(mask) => self.text.chars().map(|c| if c == '\n' {c} else {mask}).collect(),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Returns a [`TextState`] for drawing the editor.
+    pub fn state(&self) -> TextState {
+        let mut text = self.display_text();
+        match &self.preedit {
+            Some((preedit, cursor)) => {
+                let len = preedit.chars().count();
+                text.insert_str(self.byte(&text, self.caret), preedit);
+                TextState{
+                    text,
+                    caret: self.caret + cursor.map_or(len, |(_, end)| end),
+                    selection: None,
+                    composition: Some(self.caret..self.caret + len),
+                }
+            },
+            None => TextState{text, caret: self.caret, selection: self.selection(), composition: None},
+        }
+    }
+
+    /// Returns the text being composed with an input method.
+    pub fn preedit(&self) -> Option<&str> {self.preedit.as_ref().map(|(text, _)| text.as_str())}
+
+    /// Sets the text being composed at the caret, an empty `text` ends the composition.
+    ///
+    /// `cursor` is a char range within `text`, see [`ImeEvent::Preedit`](crate::events::ImeEvent::Preedit).
+    pub fn set_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        self.preedit = (!text.is_empty()).then(|| (text.to_string(), cursor));
+    }
+
+    /// Ends the composition and inserts the composed `text`. Returns `true` if the text changed.
+    pub fn commit(&mut self, text: &str) -> bool {
+        self.preedit = None;
+        self.insert(text)
+    }
+
+    /// Moves the caret to `index`, extending the selection from the previous caret when `extend` is set.
+    pub fn place_caret(&mut self, index: usize, extend: bool) {
+        self.set_caret(index.min(self.len()), extend);
+    }
+
+    /// Selects the whole text.
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.caret = self.len();
+    }
+
+    /// Moves the caret, extending the selection when `extend` is set.
+    ///
+    /// Without `extend`, moving left or right collapses a selection to its start or end.
+    pub fn move_caret(&mut self, motion: Motion, extend: bool) {
+        if !extend && let Some(range) = self.selection() {
+            match motion {
+                Motion::Left => return self.set_caret(range.start, false),
+                Motion::Right => return self.set_caret(range.end, false),
+                _ => {}
+            }
+        }
+        let target = self.target(motion);
+        self.set_caret(target, extend);
+    }
+
+    /// Replaces the selection with `text`, dropping the characters rejected by the filter
+    /// or exceeding the maximum length. Returns `true` if the text changed.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let selection = self.selection().unwrap_or(self.caret..self.caret);
+        let remaining = self.text.chars().take(selection.start)
+            .chain(self.text.chars().skip(selection.end)).collect::<String>();
+        let room = self.max_length.map_or(usize::MAX, |max| max.saturating_sub(remaining.chars().count()));
+        let mut accepted = String::new();
+        for c in text.chars().filter(|c| self.multiline || *c != '\n') {
+            if accepted.chars().count() >= room { break; }
+            let mut preview = remaining.clone();
+            preview.insert_str(self.byte(&remaining, selection.start), &accepted);
+            if self.filter.accepts(c, &preview, selection.start + accepted.chars().count()) {
+                accepted.push(c);
+            }
+        }
+        if accepted.is_empty() { return false; }
+
+        let kind = match accepted.chars().count() == 1 && selection.is_empty() {
+            true => Edit::Typing,
+            false => Edit::Other,
+        };
+        self.checkpoint(kind);
+        self.replace(selection.clone(), &accepted);
+        self.caret = selection.start + accepted.chars().count();
+        true
+    }
+
+    /// Deletes the selection, or the text between the caret and the target of `motion`.
+    /// Returns `true` if the text changed.
+    pub fn delete(&mut self, motion: Motion) -> bool {
+        let range = self.selection().unwrap_or_else(|| {
+            let target = self.target(motion);
+            target.min(self.caret)..target.max(self.caret)
+        });
+        if range.is_empty() { return false; }
+        self.checkpoint(Edit::Deleting);
+        self.replace(range.clone(), "");
+        self.caret = range.start;
+        true
+    }
+
+    /// Reverts the last edit. Returns `true` if there was one.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo.pop() else { return false };
+        self.redo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    /// Reapplies the last undone edit. Returns `true` if there was one.
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo.pop() else { return false };
+        self.undo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    /// Applies a key press, returning `true` if the key was handled.
+    ///
+    /// Typing inserts the text produced by the key, which includes the characters composed with dead keys.
+    /// Handles typing, Backspace and Delete, arrows, Home and End, Enter in multiline editors,
+    /// and the select all, undo and redo shortcuts. The clipboard shortcuts are handled by
+    /// [`TextInput`](crate::emitters::TextInput), see [`TextEditor::copy`] and [`TextEditor::cut`]. Holding Shift extends the selection,
+    /// holding Alt on Apple platforms or Control elsewhere moves by words.
+    /// Characters typed with AltGr, reported as Control and Alt, are inserted.
+    pub fn handle_key(&mut self, event: &KeyboardEvent) -> bool {
+        let KeyboardEvent{key, modifiers, text, ..} = event;
+        let modifiers = *modifiers;
+        let typed = text.as_deref().filter(|text| !text.chars().any(char::is_control));
+        let extend = modifiers.shift;
+        let word = match Modifiers::APPLE {
+            true => modifiers.alt,
+            false => modifiers.control,
+        };
+        let line = Modifiers::APPLE && modifiers.meta;
+        //AltGr is reported as Control and Alt, and types text
+        let altgr = modifiers.control && modifiers.alt && typed.is_some();
+        match key {
+            Key::Character(c) if modifiers.command() && !altgr => match c.to_lowercase().as_str() {
+                "a" => {
+                    self.select_all();
+                    true
+                },
+                "z" if modifiers.shift => self.redo(),
+                "z" => self.undo(),
+                "y" if !Modifiers::APPLE => self.redo(),
+                _ => false,
+            },
+            Key::Character(_) | Key::Dead(_) if modifiers.control && !altgr => false,
+            Key::Character(c) => self.insert(typed.unwrap_or(c)),
+            Key::Dead(_) => typed.is_some_and(|typed| self.insert(typed)),
+            Key::Named(NamedKey::Space) => self.insert(" "),
+            Key::Named(NamedKey::Enter) if self.multiline => self.insert("\n"),
+            Key::Named(NamedKey::Backspace) => self.delete(match (line, word) {
+                (true, _) => Motion::LineStart,
+                (_, true) => Motion::WordLeft,
+                _ => Motion::Left,
+            }),
+            Key::Named(NamedKey::Delete) => self.delete(match (line, word) {
+                (true, _) => Motion::LineEnd,
+                (_, true) => Motion::WordRight,
+                _ => Motion::Right,
+            }),
+            Key::Named(named) => {
+                let motion = match named {
+                    NamedKey::ArrowLeft if line => Motion::LineStart,
+                    NamedKey::ArrowLeft if word => Motion::WordLeft,
+                    NamedKey::ArrowLeft => Motion::Left,
+                    NamedKey::ArrowRight if line => Motion::LineEnd,
+                    NamedKey::ArrowRight if word => Motion::WordRight,
+                    NamedKey::ArrowRight => Motion::Right,
+                    NamedKey::ArrowUp if line => Motion::Start,
+                    NamedKey::ArrowUp => Motion::Up,
+                    NamedKey::ArrowDown if line => Motion::End,
+                    NamedKey::ArrowDown => Motion::Down,
+                    NamedKey::Home if modifiers.control => Motion::Start,
+                    NamedKey::Home => Motion::LineStart,
+                    NamedKey::End if modifiers.control => Motion::End,
+                    NamedKey::End => Motion::LineEnd,
+                    _ => return false,
+                };
+                self.move_caret(motion, extend);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns the char index closest to `position`, relative to the top left of the text.
+    ///
+    /// `measure` returns the width of a line of the displayed text, and every line is `line_height` tall.
+    pub fn index_at(&self, position: (f32, f32), line_height: f32, mut measure: impl FnMut(&str) -> f32) -> usize {
+        let display = self.display_text();
+        let lines = display.split('\n').collect::<Vec<_>>();
+        let row = ((position.1 / line_height.max(f32::EPSILON)).floor().max(0.0) as usize).min(lines.len() - 1);
+        let start = lines[..row].iter().map(|line| line.chars().count() + 1).sum::<usize>();
+
+        let line = lines[row];
+        let mut previous = 0.0;
+        for (i, end) in line.char_indices().map(|(b, c)| b + c.len_utf8()).enumerate() {
+            let width = measure(&line[..end]);
+            if position.0 < (previous + width) / 2.0 {
+                return start + i;
+            }
+            previous = width;
+        }
+        start + line.chars().count()
+    }
+
+    fn len(&self) -> usize {self.text.chars().count()}
+
+    fn byte(&self, text: &str, index: usize) -> usize {
+        text.char_indices().nth(index).map_or(text.len(), |(b, _)| b)
+    }
+
+    fn set_caret(&mut self, index: usize, extend: bool) {
+        match extend {
+            true => { self.anchor.get_or_insert(self.caret); },
+            false => self.anchor = None,
+        }
+        self.caret = index;
+        self.last_edit = None;
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        let (start, end) = (self.byte(&self.text, range.start), self.byte(&self.text, range.end));
+        self.text.replace_range(start..end, text);
+        self.anchor = None;
+    }
+
+    /// Returns the (line start, column) of a char index.
+    fn line_of(&self, index: usize) -> (usize, usize) {
+        let start = self.text.chars().take(index).collect::<Vec<_>>()
+            .iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1);
+        (start, index - start)
+    }
+
+    fn line_end(&self, index: usize) -> usize {
+        self.text.chars().skip(index).position(|c| c == '\n').map_or(self.len(), |i| index + i)
+    }
+
+    fn target(&self, motion: Motion) -> usize {
+        let chars = self.text.chars().collect::<Vec<_>>();
+        let class = |c: char| match c {
+            c if c.is_whitespace() => 0,
+            c if c.is_alphanumeric() || c == '_' => 1,
+            _ => 2,
+        };
+        match motion {
+            Motion::Left => self.caret.saturating_sub(1),
+            Motion::Right => (self.caret + 1).min(chars.len()),
+            Motion::Start => 0,
+            Motion::End => chars.len(),
+            Motion::WordLeft if self.mask.is_some() => 0,
+            Motion::WordRight if self.mask.is_some() => chars.len(),
+            Motion::WordLeft => {
+                let mut i = self.caret;
+                while i > 0 && class(chars[i - 1]) == 0 { i -= 1; }
+                let word = i.checked_sub(1).map(|i| class(chars[i]));
+                while i > 0 && Some(class(chars[i - 1])) == word { i -= 1; }
+                i
+            },
+            Motion::WordRight => {
+                let mut i = self.caret;
+                while i < chars.len() && class(chars[i]) == 0 { i += 1; }
+                let word = chars.get(i).map(|c| class(*c));
+                while i < chars.len() && Some(class(chars[i])) == word { i += 1; }
+                i
+            },
+            Motion::LineStart => self.line_of(self.caret).0,
+            Motion::LineEnd => self.line_end(self.caret),
+            Motion::Up => {
+                let (start, column) = self.line_of(self.caret);
+                match start {
+                    0 => 0,
+                    _ => {
+                        let previous = self.line_of(start - 1).0;
+                        previous + column.min(start - 1 - previous)
+                    }
+                }
+            },
+            Motion::Down => {
+                let column = self.line_of(self.caret).1;
+                let end = self.line_end(self.caret);
+                match end == chars.len() {
+                    true => end,
+                    false => (end + 1 + column).min(self.line_end(end + 1)),
+                }
+            },
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot{text: self.text.clone(), caret: self.caret, anchor: self.anchor}
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.text = snapshot.text;
+        self.caret = snapshot.caret;
+        self.anchor = snapshot.anchor;
+        self.last_edit = None;
+    }
+
+    /// Saves the state for undo, merging consecutive typing or deleting into a single step.
+    fn checkpoint(&mut self, edit: Edit) {
+        if edit == Edit::Other || self.last_edit != Some(edit) {
+            self.undo.push(self.snapshot());
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.last_edit = Some(edit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{KeyboardState, SmolStr};
+
+    fn editor(text: &str) -> TextEditor {
+        let mut editor = TextEditor::new(true);
+        editor.set_text(text);
+        editor
+    }
+
+    fn press(key: Key, modifiers: Modifiers, text: Option<&str>) -> KeyboardEvent {
+        KeyboardEvent{key, state: KeyboardState::Pressed, modifiers, text: text.map(SmolStr::new)}
+    }
+
+    #[test]
+    fn word_motions_skip_whitespace_and_punctuation() {
+        let mut editor = editor("foo_bar, baz");
+        editor.place_caret(0, false);
+        editor.move_caret(Motion::WordRight, false);
+        assert_eq!(editor.caret(), 7);
+        editor.move_caret(Motion::WordRight, false);
+        assert_eq!(editor.caret(), 8);
+        editor.move_caret(Motion::WordRight, false);
+        assert_eq!(editor.caret(), 12);
+        editor.move_caret(Motion::WordLeft, false);
+        assert_eq!(editor.caret(), 9);
+    }
+
+    #[test]
+    fn vertical_motions_keep_the_column() {
+        let mut editor = editor("abcd\nx\nefgh");
+        editor.place_caret(3, false);
+        editor.move_caret(Motion::Down, false);
+        assert_eq!(editor.caret(), 6);
+        editor.move_caret(Motion::Down, false);
+        assert_eq!(editor.caret(), 8);
+        editor.move_caret(Motion::Up, false);
+        assert_eq!(editor.caret(), 6);
+        editor.move_caret(Motion::LineStart, false);
+        assert_eq!(editor.caret(), 5);
+        editor.move_caret(Motion::End, true);
+        assert_eq!(editor.selection(), Some(5..11));
+    }
+
+    #[test]
+    fn moving_collapses_the_selection() {
+        let mut editor = editor("hello");
+        editor.move_caret(Motion::WordLeft, true);
+        assert_eq!(editor.selected_text(), Some("hello".to_string()));
+        editor.move_caret(Motion::Left, false);
+        assert_eq!((editor.caret(), editor.selection()), (0, None));
+    }
+
+    #[test]
+    fn masked_word_motions_jump_to_the_ends() {
+        let mut editor = editor("secret words");
+        *editor.mask() = Some('•');
+        editor.move_caret(Motion::WordLeft, false);
+        assert_eq!(editor.caret(), 0);
+        editor.move_caret(Motion::WordRight, true);
+        assert_eq!(editor.copy(), None);
+    }
+
+    #[test]
+    fn undo_merges_typing_and_redo_restores_it() {
+        let mut editor = editor("");
+        for c in ["a", "b", "c"] { editor.insert(c); }
+        editor.delete(Motion::Left);
+        assert_eq!(editor.text(), "ab");
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "abc");
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "");
+        assert!(!editor.undo());
+        assert!(editor.redo());
+        assert_eq!((editor.text(), editor.caret()), ("abc", 3));
+        editor.insert("d");
+        assert!(!editor.redo());
+    }
+
+    #[test]
+    fn caret_moves_split_undo_steps() {
+        let mut editor = editor("");
+        editor.insert("a");
+        editor.move_caret(Motion::Start, false);
+        editor.insert("b");
+        editor.undo();
+        assert_eq!(editor.text(), "a");
+    }
+
+    #[test]
+    fn filters_and_max_length_drop_characters() {
+        let mut editor = TextEditor::new(false);
+        *editor.filter() = InputFilter::Decimal;
+        *editor.max_length() = Some(5);
+        editor.insert("-1a.2.34\n5");
+        assert_eq!(editor.text(), "-1.23");
+    }
+
+    #[test]
+    fn altgr_characters_are_typed() {
+        let mut editor = editor("");
+        let altgr = Modifiers{control: true, alt: true, ..Modifiers::default()};
+        assert!(editor.handle_key(&press(Key::Character(SmolStr::new("@")), altgr, Some("@"))));
+        let control = Modifiers{control: true, ..Modifiers::default()};
+        assert!(!editor.handle_key(&press(Key::Character(SmolStr::new("q")), control, None)));
+        assert_eq!(editor.text(), "@");
+    }
+}
@@ -1,19 +1,27 @@
-use crate::events::{Event, TickEvent, MouseEvent, MouseState, KeyboardEvent, KeyboardState};
+use crate::events::{Event, TickEvent, MouseEvent, MouseState, KeyboardEvent, KeyboardState, FocusEvent};
+use crate::events::{PointerEvent, PointerKind, PointerState, SwipeDirection, DragEvent, DragState, Payload};
+use crate::events::{PointerEnter, PointerLeave, CursorIcon, Key, NamedKey, ImeEvent};
+use crate::editor::TextEditor;
+use crate::drawable::{Shape, ShapeType, Color};
 use crate::{events, Drawable, Context, Component};
 use crate::events::OnEvent;
 use crate::layouts::Stack;
+use std::any::Any;
 use std::time::Duration;
+use std::f32::consts::{PI, TAU};
 
 /// The [`Button`] emitter wraps a drawable component
 /// and converts mouse input into a small set of semantic button states:
 ///
 /// - [`Button::Pressed(true)`](crate::events::Button::Pressed) — when the mouse is pressed within the button’s bounds.
 /// - [`Button::Pressed(false)`](crate::events::Button::Pressed) — when the mouse is pressed outside the button’s bounds.
-/// - [`Button::Hover(true)`](crate::events::Button::Hover) — when the mouse moves over the button.
+/// - [`Button::Hover(true)`](crate::events::Button::Hover) — when the mouse enters the button.
 /// - [`Button::Hover(false)`](crate::events::Button::Hover) — when the mouse leaves the button.
 ///
 /// This allows components to react to common button states without manually handling raw input.
 ///
+/// [`Button::Pressed(true)`](crate::events::Button::Pressed) is also bubbled up to the button's ancestors,
+/// see [`OnEvent::on_bubble`].
 #[derive(Debug)]
 pub struct Button<D: Drawable + 'static>(Stack, pub D);
 impl<D: Drawable + 'static> Button<D> {
@@ -38,13 +46,13 @@ impl<D: Drawable + 'static> Component for Button<D> {
 }
 
 impl<D: Drawable + 'static> OnEvent for Button<D> {
-    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
         if let Some(event) = event.downcast_ref::<MouseEvent>() {
             return match event.state {
-                MouseState::Pressed if event.position.is_some() => 
-                    events![events::Button::Pressed(true)],
-                MouseState::Moved | MouseState::Scroll(..) => 
-                    events![events::Button::Hover(event.position.is_some())],
+                MouseState::Pressed if event.position.is_some() => {
+                    ctx.bubble(events::Button::Pressed(true));
+                    events![events::Button::Pressed(true)]
+                },
                 MouseState::Released => {
                     match !crate::IS_MOBILE && event.position.is_some() {
                         true => events![events::Button::Hover(true)],
@@ -53,9 +61,15 @@ impl<D: Drawable + 'static> OnEvent for Button<D> {
                 },
                 _ => Vec::new()
             };
+        } else if event.downcast_ref::<PointerEnter>().is_some() {
+            return events![events::Button::Hover(true)];
+        } else if event.downcast_ref::<PointerLeave>().is_some() {
+            return events![events::Button::Hover(false)];
         }
         vec![event]
     }
+
+    fn hoverable(&self) -> bool {true}
 }
 
 /// The [`Selectable`] emitter allows one item in a group to be active at a time. 
@@ -94,10 +108,9 @@ impl<D: Drawable + 'static> OnEvent for Selectable<D> {
     fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
         if let Some(MouseEvent { state: MouseState::Pressed, position: Some(_) }) = event.downcast_ref::<MouseEvent>() {
             ctx.trigger_event(events::Selectable::Pressed(self.2, self.3));
-        } else if let Some(events::Selectable::Pressed(id, group_id)) = event.downcast_ref::<events::Selectable>() {
-            if *group_id == self.3 {
-                return events![events::Selectable::Selected(*id == self.2)];
-            }
+        } else if let Some(events::Selectable::Pressed(id, group_id)) = event.downcast_ref::<events::Selectable>()
+            && *group_id == self.3 {
+            return events![events::Selectable::Selected(*id == self.2)];
         }
         vec![event]
     }
@@ -109,6 +122,9 @@ impl<D: Drawable + 'static> OnEvent for Selectable<D> {
 /// - [`Slider::Start(x)`](crate::events::Slider::Start) — when the user clicks or begins dragging.
 /// - [`Slider::Moved(x)`](crate::events::Slider::Moved) — while dragging with the mouse pressed.
 /// - Automatically stops tracking when released.
+///
+/// The slider captures the pointer when pressed, so dragging keeps reporting `x`,
+/// relative to the slider's origin, after the pointer leaves its bounds.
 #[derive(Debug)]
 pub struct Slider<D: Drawable + 'static>(Stack, pub D, bool);
 impl<D: Drawable + 'static> Slider<D> {
@@ -133,11 +149,12 @@ impl<D: Drawable + 'static> Component for Slider<D> {
 }
 
 impl<D: Drawable + 'static> OnEvent for Slider<D> {
-    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
         if let Some(MouseEvent { state, position, }) = event.downcast_ref::<MouseEvent>() {
             return match (state, position) {
                 (MouseState::Pressed, Some((x, _))) => {
                     self.2 = true;
+                    ctx.capture_pointer();
                     events![events::Slider::Start(*x)]
                 },
                 (MouseState::Released, _) => {
@@ -155,55 +172,228 @@ impl<D: Drawable + 'static> OnEvent for Slider<D> {
     }
 }
 
+/// The [`Focusable`] emitter wraps a drawable component and makes it
+/// reachable with the keyboard focus, see [`Context::focus`]:
+///
+/// - [`Focusable::Focused(true)`](crate::events::Focusable::Focused) — when focused, by a click inside bounds or with Tab.
+/// - [`Focusable::Focused(false)`](crate::events::Focusable::Focused) — when the focus moved elsewhere.
+/// - Keyboard events only reach the child while it, or one of its descendants, is focused.
+#[derive(Debug)]
+pub struct Focusable<D: Drawable + 'static>(Stack, pub D, uuid::Uuid, Option<u32>);
+impl<D: Drawable + 'static> Focusable<D> {
+    pub fn new(child: D) -> Self {Focusable(Stack::default(), child, uuid::Uuid::new_v4(), None)}
+
+    /// Returns the id used with [`Context::focus`] and [`Context::is_focused`].
+    pub fn id(&self) -> uuid::Uuid {self.2}
+    /// Returns a mutable reference to the tab index, `None` to follow the tree order.
+    pub fn tab_index(&mut self) -> &mut Option<u32> {&mut self.3}
+}
+
+impl<D: Drawable + 'static> Component for Focusable<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        ctx.register_focusable(self.2, self.3);
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for Focusable<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{state: MouseState::Pressed, position}) = event.downcast_ref::<MouseEvent>() {
+            match position {
+                Some(_) => ctx.focus(self.2),
+                None if ctx.is_focused(self.2) => ctx.blur(),
+                None => {}
+            }
+        } else if let Some(focus) = event.downcast_ref::<FocusEvent>() {
+            match focus {
+                FocusEvent::Gained(id) if *id == self.2 => return events![events::Focusable::Focused(true), *focus],
+                FocusEvent::Lost(id) if *id == self.2 => return events![events::Focusable::Focused(false), *focus],
+                _ => {}
+            }
+        }
+        vec![event]
+    }
+}
+
+type MeasureFunc = dyn FnMut(&mut Context, &str) -> f32;
+
+/// Measures the displayed text of a [`TextInput`] to place the caret under the mouse.
+struct TextMeasure {
+    line_height: f32,
+    width: Box<MeasureFunc>,
+}
+
+impl std::fmt::Debug for TextMeasure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TextMeasure({})", self.line_height)
+    }
+}
+
 /// The [`TextInput`] emitter wraps a drawable component
-/// and converts raw input into a small set of semantic text input states:
+/// and edits its text with a [`TextEditor`], converting raw input into a small set of semantic text input states:
 ///
-/// - [`TextInput::Focused(true)`](crate::events::TextInput::Focused) — when focused (clicked inside bounds).
-/// - [`TextInput::Focused(false)`](crate::events::TextInput::Focused) — when unfocused (clicked outside bounds).
-/// - [`TextInput::Hover(true)`](crate::events::TextInput::Hover) — when the mouse hovers over the input.
+/// - [`TextInput::Focused(true)`](crate::events::TextInput::Focused) — when focused (clicked inside bounds or reached with Tab).
+/// - [`TextInput::Focused(false)`](crate::events::TextInput::Focused) — when unfocused (clicked outside bounds or focus moved elsewhere).
+/// - [`TextInput::Hover(true)`](crate::events::TextInput::Hover) — when the mouse enters the input.
 /// - [`TextInput::Hover(false)`](crate::events::TextInput::Hover) — when the mouse leaves the input.
+/// - [`TextInput::Edited`](crate::events::TextInput::Edited) — when the text, caret or selection changes, with the state to draw.
+/// - [`TextInput::Submitted`](crate::events::TextInput::Submitted) — when Enter is pressed in a single line input.
 /// - Passes keyboard events through only when focused.
+/// - Shows the [`CursorIcon::Text`] cursor while hovered.
+///
+/// Copy, cut and paste with the platform shortcuts go through [`Context::clipboard`].
+///
+/// While focused, text composed with an input method, see [`ImeEvent`], is reported in
+/// [`TextState::composition`](crate::editor::TextState::composition) and underlined once [`TextInput::measure`] is set.
+/// The caret area is reported with [`Context::set_ime_area`]. The window does not support
+/// input methods yet, so composition only happens with [`RawInput::Ime`](crate::events::RawInput::Ime).
+///
+/// Pressing and dragging the mouse places the caret and selects text once [`TextInput::measure`] is set,
+/// otherwise a press moves the caret to the end of the text.
+///
+/// Focus is tracked by the [`Context`], see [`Context::focus`].
 #[derive(Debug)]
-pub struct TextInput<D: Drawable + 'static>(Stack, pub D, bool);
+pub struct TextInput<D: Drawable + 'static> {
+    layout: Stack,
+    pub inner: D,
+    id: uuid::Uuid,
+    tab_index: Option<u32>,
+    editor: TextEditor,
+    measure: Option<TextMeasure>,
+    selecting: bool,
+    underline: Shape,
+}
 impl<D: Drawable + 'static> TextInput<D> {
-    pub fn new(child: D) -> Self {TextInput(Stack::default(), child, false)}
+    pub fn new(child: D) -> Self {
+        TextInput{
+            layout: Stack::default(), inner: child, id: uuid::Uuid::new_v4(), tab_index: None,
+            editor: TextEditor::new(false), measure: None, selecting: false,
+            underline: Shape::new(ShapeType::Rectangle(0.0, (0.0, 0.0), 0.0), Color(0, 0, 0, 255)),
+        }
+    }
+
+    /// Returns the id used with [`Context::focus`] and [`Context::is_focused`].
+    pub fn id(&self) -> uuid::Uuid {self.id}
+    /// Returns a mutable reference to the tab index, `None` to follow the tree order.
+    pub fn tab_index(&mut self) -> &mut Option<u32> {&mut self.tab_index}
+    /// Returns a mutable reference to the [`TextEditor`].
+    pub fn editor(&mut self) -> &mut TextEditor {&mut self.editor}
+    /// Returns a mutable reference to the color of the line drawn under composed text.
+    pub fn underline(&mut self) -> &mut Color {&mut self.underline.color}
+
+    /// Sets how the displayed text is measured for mouse selection:
+    /// `width` returns the width of a line of text and every line is `line_height` tall.
+    ///
+    /// The text is assumed to start at the top left of the input.
+    pub fn measure(&mut self, line_height: f32, width: impl FnMut(&mut Context, &str) -> f32 + 'static) {
+        self.measure = Some(TextMeasure{line_height, width: Box::new(width)});
+    }
+
+    /// Returns the area of the caret, or of the whole input without [`TextInput::measure`].
+    fn caret_area(&mut self, ctx: &mut Context, size: (f32, f32)) -> ((f32, f32), (f32, f32)) {
+        let Some(measure) = &mut self.measure else { return ((0.0, 0.0), size) };
+        let state = self.editor.state();
+        let before = state.text.chars().take(state.caret).collect::<String>();
+        let row = before.matches('\n').count();
+        let line = before.rsplit('\n').next().unwrap_or_default();
+        let x = (measure.width)(ctx, line);
+        ((x, row as f32 * measure.line_height), (1.0, measure.line_height))
+    }
+
+    /// Sizes the line under the composed text, returning its offset.
+    fn sync_underline(&mut self, ctx: &mut Context) -> (f32, f32) {
+        let (offset, width) = self.composition_underline(ctx).unwrap_or_default();
+        self.underline.shape = ShapeType::Rectangle(0.0, (width, 1.0), 0.0);
+        offset
+    }
+
+    fn composition_underline(&mut self, ctx: &mut Context) -> Option<((f32, f32), f32)> {
+        let measure = self.measure.as_mut()?;
+        let state = self.editor.state();
+        let composition = state.composition?;
+        let before = state.text.chars().take(composition.start).collect::<String>();
+        let composed = state.text.chars().skip(composition.start).take(composition.len()).collect::<String>();
+        let row = before.matches('\n').count();
+        let line = before.rsplit('\n').next().unwrap_or_default();
+        let start = (measure.width)(ctx, line);
+        let end = (measure.width)(ctx, &format!("{line}{composed}"));
+        Some(((start, (row + 1) as f32 * measure.line_height - 1.0), end - start))
+    }
+
+    fn index_at(&mut self, ctx: &mut Context, position: (f32, f32)) -> usize {
+        match &mut self.measure {
+            Some(measure) => self.editor.index_at(position, measure.line_height, |line| (measure.width)(ctx, line)),
+            None => self.editor.text().chars().count(),
+        }
+    }
 }
 
 impl<D: Drawable + 'static> Component for TextInput<D> {
     fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
-        &mut self.1 as &mut dyn crate::drawable::Drawable,
+        &mut self.inner as &mut dyn crate::drawable::Drawable,
+        &mut self.underline as &mut dyn crate::drawable::Drawable,
     ]}
 
     fn children(&self) -> Vec<&dyn Drawable> {vec![
-        &self.1 as &dyn crate::drawable::Drawable,
+        &self.inner as &dyn crate::drawable::Drawable,
+        &self.underline as &dyn crate::drawable::Drawable,
     ]}
 
     fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
-        crate::layout::Layout::request_size(&self.0, ctx, children)
+        crate::layout::Layout::request_size(&self.layout, ctx, children)
     }
     fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
-        crate::layout::Layout::build(&self.0, ctx, size, children)
+        ctx.register_focusable(self.id, self.tab_index);
+        if ctx.is_focused(self.id) {
+            let (offset, size) = self.caret_area(ctx, size);
+            ctx.set_ime_area(offset, size);
+        }
+        let offset = self.sync_underline(ctx);
+        let mut areas = crate::layout::Layout::build(&self.layout, ctx, size, children);
+        areas[1] = crate::layout::Area{offset, size: self.underline.shape.size()};
+        areas
     }
 }
 
 impl<D: Drawable + 'static> OnEvent for TextInput<D> {
-    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
         if let Some(e) = event.downcast_ref::<MouseEvent>() {
             let mut events: Vec<Box<dyn Event>> = Vec::new();
 
-            match e.state {
-                MouseState::Pressed if e.position.is_some() => {
-                    self.2 = true;
-                    events.push(Box::new(events::TextInput::Focused(true)));
-                }
-                MouseState::Pressed if e.position.is_none() => self.2 = false,
-                MouseState::Moved | MouseState::Scroll(..) => {
-                    events.push(Box::new(events::TextInput::Hover(e.position.is_some())));
-                }
-                MouseState::Released => {
-                    match !crate::IS_MOBILE && e.position.is_some() {
-                        true => events.push(Box::new(events::TextInput::Hover(true))),
-                        false => events.push(Box::new(events::TextInput::Focused(false))),
+            if e.position.is_some() {
+                ctx.set_cursor(CursorIcon::Text);
+            }
+
+            match (e.state, e.position) {
+                (MouseState::Pressed, Some(position)) => {
+                    ctx.focus(self.id);
+                    ctx.capture_pointer();
+                    let index = self.index_at(ctx, position);
+                    self.editor.place_caret(index, false);
+                    self.selecting = true;
+                    events.push(Box::new(events::TextInput::Edited(self.editor.state())));
+                },
+                (MouseState::Pressed, None) if ctx.is_focused(self.id) => ctx.blur(),
+                (MouseState::Moved, Some(position)) if self.selecting && self.measure.is_some() => {
+                    let index = self.index_at(ctx, position);
+                    self.editor.place_caret(index, true);
+                    events.push(Box::new(events::TextInput::Edited(self.editor.state())));
+                },
+                (MouseState::Released, position) => {
+                    self.selecting = false;
+                    if !crate::IS_MOBILE && position.is_some() {
+                        events.push(Box::new(events::TextInput::Hover(true)));
                     }
                 }
                 _ => {}
@@ -212,12 +402,535 @@ impl<D: Drawable + 'static> OnEvent for TextInput<D> {
             events.push(event);
 
             return events;
-        } else if let Some(KeyboardEvent { state: KeyboardState::Pressed, key: _ }) = event.downcast_ref() {
-            return if self.2 { vec![event] } else { Vec::new() };
+        } else if let Some(focus) = event.downcast_ref::<FocusEvent>() {
+            match focus {
+                FocusEvent::Gained(id) if *id == self.id => return events![events::TextInput::Focused(true), *focus],
+                FocusEvent::Lost(id) if *id == self.id => return events![events::TextInput::Focused(false), *focus],
+                _ => {}
+            }
+        } else if event.downcast_ref::<PointerEnter>().is_some() {
+            return events![events::TextInput::Hover(true)];
+        } else if event.downcast_ref::<PointerLeave>().is_some() {
+            return events![events::TextInput::Hover(false)];
+        } else if let Some(ime) = event.downcast_ref::<ImeEvent>() {
+            if !ctx.is_focused(self.id) { return Vec::new(); }
+            let edited = match ime {
+                ImeEvent::Preedit(text, cursor) => {
+                    self.editor.set_preedit(text, *cursor);
+                    true
+                },
+                ImeEvent::Commit(text) => {
+                    self.editor.commit(text);
+                    true
+                },
+                ImeEvent::Disabled if self.editor.preedit().is_some() => {
+                    self.editor.set_preedit("", None);
+                    true
+                },
+                _ => false,
+            };
+            //Sized before the next layout pass requests it
+            self.sync_underline(ctx);
+            return match edited {
+                true => vec![Box::new(events::TextInput::Edited(self.editor.state())) as Box<dyn Event>, event],
+                false => vec![event],
+            };
+        } else if let Some(keyboard @ KeyboardEvent { key, state: KeyboardState::Pressed, modifiers, .. }) = event.downcast_ref() {
+            if !ctx.is_focused(self.id) { return Vec::new(); }
+            let edited = match key {
+                Key::Named(NamedKey::Enter) if !self.editor.is_multiline() => {
+                    return vec![Box::new(events::TextInput::Submitted(self.editor.text().to_string())) as Box<dyn Event>, event];
+                },
+                Key::Character(c) if modifiers.command() && !modifiers.shift && c.eq_ignore_ascii_case("c") => {
+                    if let Some(text) = self.editor.copy() { ctx.clipboard().set_text(text); }
+                    false
+                },
+                Key::Character(c) if modifiers.command() && !modifiers.shift && c.eq_ignore_ascii_case("x") => {
+                    self.editor.cut().map(|text| ctx.clipboard().set_text(text)).is_some()
+                },
+                Key::Character(c) if modifiers.command() && !modifiers.shift && c.eq_ignore_ascii_case("v") => {
+                    ctx.clipboard().text().is_some_and(|text| self.editor.insert(&text))
+                },
+                _ => self.editor.handle_key(keyboard),
+            };
+            let edit: Box<dyn Event> = match edited {
+                true => Box::new(events::TextInput::Edited(self.editor.state())),
+                false => return vec![event],
+            };
+            return vec![edit, event];
         }
 
         vec![event]
     }
+
+    fn hoverable(&self) -> bool {true}
+}
+
+/// The [`Cursor`] emitter wraps a drawable component and shows
+/// the given [`CursorIcon`] while the pointer is over it, see [`Context::set_cursor`].
+#[derive(Debug)]
+pub struct Cursor<D: Drawable + 'static>(Stack, pub D, CursorIcon);
+impl<D: Drawable + 'static> Cursor<D> {
+    pub fn new(child: D, icon: CursorIcon) -> Self {Cursor(Stack::default(), child, icon)}
+
+    /// Returns a mutable reference to the cursor icon.
+    pub fn icon(&mut self) -> &mut CursorIcon {&mut self.2}
+}
+
+impl<D: Drawable + 'static> Component for Cursor<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for Cursor<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{position: Some(_), ..}) = event.downcast_ref::<MouseEvent>() {
+            ctx.set_cursor(self.2);
+        }
+        vec![event]
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// The [`LongPress`] emitter wraps a drawable component and emits
+/// [`LongPress(position)`](crate::events::LongPress) once a pointer has been held
+/// within its bounds for `duration`.
+///
+/// The press is cancelled when the pointer is released, leaves the bounds
+/// or moves more than `tolerance` logical pixels. All events are passed on to the child.
+#[derive(Debug)]
+pub struct LongPress<D: Drawable + 'static>(Stack, pub D, Duration, f32, Option<Press>);
+
+/// Pointer kind and id, press time and position of a tracked press.
+type Press = (PointerKind, u64, Duration, (f32, f32));
+
+impl<D: Drawable + 'static> LongPress<D> {
+    pub fn new(child: D, duration: Duration, tolerance: f32) -> Self {
+        LongPress(Stack::default(), child, duration, tolerance, None)
+    }
+}
+
+impl<D: Drawable + 'static> Component for LongPress<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for LongPress<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(&PointerEvent{id, kind, position, state}) = event.downcast_ref::<PointerEvent>() {
+            let tracked = self.4.is_some_and(|(k, i, ..)| k == kind && i == id);
+            match (state, position) {
+                (PointerState::Pressed, Some(position)) => self.4 = Some((kind, id, ctx.time(), position)),
+                (PointerState::Moved, Some(position)) if tracked =>
+                    self.4 = self.4.filter(|(.., start)| distance(*start, position) <= self.3),
+                _ if tracked && state != PointerState::Pressed => self.4 = None,
+                _ => {}
+            }
+        } else if event.downcast_ref::<TickEvent>().is_some()
+            && let Some((.., start, position)) = self.4
+            && ctx.time() - start >= self.2
+        {
+            self.4 = None;
+            return vec![Box::new(events::LongPress(position)) as Box<dyn Event>, event];
+        }
+        vec![event]
+    }
+}
+
+/// The [`DoubleTap`] emitter wraps a drawable component and emits
+/// [`DoubleTap(position)`](crate::events::DoubleTap) when it is pressed twice
+/// within `interval`, with both presses at most `distance` logical pixels apart.
+///
+/// All events are passed on to the child.
+#[derive(Debug)]
+pub struct DoubleTap<D: Drawable + 'static>(Stack, pub D, Duration, f32, Option<(Duration, (f32, f32))>);
+
+impl<D: Drawable + 'static> DoubleTap<D> {
+    pub fn new(child: D, interval: Duration, distance: f32) -> Self {
+        DoubleTap(Stack::default(), child, interval, distance, None)
+    }
+}
+
+impl<D: Drawable + 'static> Component for DoubleTap<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for DoubleTap<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(&PointerEvent{state: PointerState::Pressed, position: Some(position), ..}) = event.downcast_ref::<PointerEvent>() {
+            let double = self.4.is_some_and(|(time, last)|
+                ctx.time() - time <= self.2 && distance(last, position) <= self.3
+            );
+            if double {
+                self.4 = None;
+                return vec![Box::new(events::DoubleTap(position)) as Box<dyn Event>, event];
+            }
+            self.4 = Some((ctx.time(), position));
+        }
+        vec![event]
+    }
+}
+
+/// The [`Swipe`] emitter wraps a drawable component and emits a
+/// [`Swipe`](crate::events::Swipe) when a pointer pressed within its bounds is released
+/// after moving at least `distance` logical pixels at an average speed of at least `velocity`
+/// logical pixels per second.
+///
+/// The direction is the axis the pointer moved the most along. All events are passed on to the child.
+#[derive(Debug)]
+pub struct Swipe<D: Drawable + 'static>(Stack, pub D, f32, f32, Option<SwipeTrack>);
+
+/// Pointer kind and id, press time, start position and last position of a tracked swipe.
+type SwipeTrack = (PointerKind, u64, Duration, (f32, f32), (f32, f32));
+
+impl<D: Drawable + 'static> Swipe<D> {
+    pub fn new(child: D, distance: f32, velocity: f32) -> Self {
+        Swipe(Stack::default(), child, distance, velocity, None)
+    }
+}
+
+impl<D: Drawable + 'static> Component for Swipe<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for Swipe<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(&PointerEvent{id, kind, position, state}) = event.downcast_ref::<PointerEvent>() {
+            let tracked = self.4.is_some_and(|(k, i, ..)| k == kind && i == id);
+            match (state, position) {
+                (PointerState::Pressed, Some(position)) => {
+                    self.4 = Some((kind, id, ctx.time(), position, position));
+                },
+                (PointerState::Moved, Some(position)) if tracked => {
+                    if let Some(swipe) = &mut self.4 { swipe.4 = position; }
+                },
+                (PointerState::Released, position) if tracked => {
+                    let Some((.., start, from, last)) = self.4.take() else { return vec![event] };
+                    let to = position.unwrap_or(last);
+                    let delta = (to.0 - from.0, to.1 - from.1);
+                    let secs = (ctx.time() - start).as_secs_f32().max(f32::EPSILON);
+                    let velocity = (delta.0 / secs, delta.1 / secs);
+                    if delta.0.hypot(delta.1) >= self.2 && velocity.0.hypot(velocity.1) >= self.3 {
+                        let direction = match delta.0.abs() > delta.1.abs() {
+                            true if delta.0 < 0.0 => SwipeDirection::Left,
+                            true => SwipeDirection::Right,
+                            false if delta.1 < 0.0 => SwipeDirection::Up,
+                            false => SwipeDirection::Down,
+                        };
+                        return vec![Box::new(events::Swipe{direction, velocity}) as Box<dyn Event>, event];
+                    }
+                },
+                (PointerState::Cancelled, _) if tracked => self.4 = None,
+                _ => {}
+            }
+        }
+        vec![event]
+    }
+}
+
+/// The [`Pinch`] emitter wraps a drawable component and turns two pointers
+/// pressed within its bounds into pinch-zoom and rotation events:
+///
+/// - [`Pinch::Start`](crate::events::Pinch::Start) — when the second pointer is pressed.
+/// - [`Pinch::Changed`](crate::events::Pinch::Changed) — when either pointer moves, with the scale and rotation since the start.
+/// - [`Pinch::End`](crate::events::Pinch::End) — when either pointer is released.
+///
+/// All events are passed on to the child.
+#[derive(Debug)]
+pub struct Pinch<D: Drawable + 'static>(Stack, pub D, Vec<(PointerKind, u64, (f32, f32))>, Option<(f32, f32)>);
+
+impl<D: Drawable + 'static> Pinch<D> {
+    pub fn new(child: D) -> Self {
+        Pinch(Stack::default(), child, Vec::new(), None)
+    }
+
+    /// Returns the distance, angle and center of the two pointers.
+    fn span(&self) -> Option<(f32, f32, (f32, f32))> {
+        match self.2.as_slice() {
+            [(.., a), (.., b)] => Some((
+                distance(*a, *b),
+                (b.1 - a.1).atan2(b.0 - a.0),
+                ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+            )),
+            _ => None
+        }
+    }
+}
+
+impl<D: Drawable + 'static> Component for Pinch<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for Pinch<D> {
+    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(&PointerEvent{id, kind, position, state}) = event.downcast_ref::<PointerEvent>() {
+            let tracked = self.2.iter().position(|(k, i, _)| *k == kind && *i == id);
+            let pinch = match (state, position, tracked) {
+                (PointerState::Pressed, Some(position), None) if self.2.len() < 2 => {
+                    self.2.push((kind, id, position));
+                    self.3 = self.span().map(|(distance, angle, _)| (distance, angle));
+                    self.3.map(|_| events::Pinch::Start)
+                },
+                (PointerState::Moved, Some(position), Some(index)) => {
+                    self.2[index].2 = position;
+                    self.3.zip(self.span()).filter(|((d0, _), _)| *d0 > 0.0).map(|((d0, a0), (d, a, center))|
+                        events::Pinch::Changed{scale: d / d0, rotation: wrap_angle(a - a0), center}
+                    )
+                },
+                (PointerState::Released | PointerState::Cancelled, _, Some(index)) => {
+                    self.2.remove(index);
+                    self.3.take().map(|_| events::Pinch::End)
+                },
+                _ => None
+            };
+            if let Some(pinch) = pinch {
+                return vec![Box::new(pinch) as Box<dyn Event>, event];
+            }
+        }
+        vec![event]
+    }
+}
+
+/// Wraps an angle in radians to (-π, π].
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    if wrapped <= -PI {PI} else {wrapped}
+}
+
+/// A drawable that follows the pointer while dragging, see [`Context::start_drag`].
+#[derive(Debug)]
+pub struct DragPreview {
+    pub drawable: Box<dyn Drawable>,
+    /// The size the preview is laid out at.
+    pub size: (f32, f32),
+    /// The point of the preview held under the pointer.
+    pub anchor: (f32, f32),
+}
+
+#[derive(Debug)]
+pub(crate) struct Drag {
+    pub payload: Payload,
+    pub preview: Option<DragPreview>,
+    pub position: (f32, f32),
+}
+
+type DragStart = dyn FnMut(&mut Context) -> (Box<dyn Any>, Option<Box<dyn Drawable>>);
+
+/// The [`Draggable`] emitter wraps a drawable component and starts a drag
+/// once the pointer pressed within its bounds moves more than `threshold` logical pixels:
+///
+/// - [`Draggable::Started`](crate::events::Draggable::Started) — when the drag starts.
+/// - [`Draggable::Ended`](crate::events::Draggable::Ended) — when the pointer is released after dragging.
+///
+/// When the drag starts, `start` returns the payload and an optional preview.
+/// The preview is laid out at the size of the draggable and held where it was pressed.
+/// Any [`DropTarget`] under the pointer receives the payload. All events are passed on to the child.
+pub struct Draggable<D: Drawable + 'static> {
+    layout: Stack,
+    pub inner: D,
+    threshold: f32,
+    size: (f32, f32),
+    press: Option<(f32, f32)>,
+    dragging: bool,
+    start: Box<DragStart>,
+}
+
+impl<D: Drawable + 'static> Draggable<D> {
+    pub fn new(
+        child: D,
+        threshold: f32,
+        start: impl FnMut(&mut Context) -> (Box<dyn Any>, Option<Box<dyn Drawable>>) + 'static
+    ) -> Self {
+        Draggable{layout: Stack::default(), inner: child, threshold, size: (0.0, 0.0), press: None, dragging: false, start: Box::new(start)}
+    }
+}
+
+impl<D: Drawable + 'static> std::fmt::Debug for Draggable<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Draggable")
+            .field("inner", &self.inner)
+            .field("threshold", &self.threshold)
+            .field("dragging", &self.dragging)
+            .finish()
+    }
+}
+
+impl<D: Drawable + 'static> Component for Draggable<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.inner as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.inner as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.layout, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        self.size = size;
+        crate::layout::Layout::build(&self.layout, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for Draggable<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(&MouseEvent{position, state}) = event.downcast_ref::<MouseEvent>() {
+            match (state, position) {
+                (MouseState::Pressed, Some(position)) => {
+                    self.press = Some(position);
+                    ctx.capture_pointer();
+                },
+                (MouseState::Moved | MouseState::Scroll(..), Some(position)) if !self.dragging => {
+                    if let Some(press) = self.press && distance(press, position) > self.threshold {
+                        self.dragging = true;
+                        let (payload, preview) = (self.start)(ctx);
+                        let preview = preview.map(|drawable| DragPreview{drawable, size: self.size, anchor: press});
+                        ctx.start_drag(payload, preview);
+                        return vec![Box::new(events::Draggable::Started) as Box<dyn Event>, event];
+                    }
+                },
+                (MouseState::Released, _) => {
+                    self.press = None;
+                    if std::mem::take(&mut self.dragging) {
+                        return vec![Box::new(events::Draggable::Ended) as Box<dyn Event>, event];
+                    }
+                },
+                _ => {}
+            }
+        }
+        vec![event]
+    }
+}
+
+/// The [`DropTarget`] emitter wraps a drawable component and reports drags
+/// started with [`Context::start_drag`], such as by a [`Draggable`], that move over it:
+///
+/// - [`DropTarget::Enter(payload)`](crate::events::DropTarget::Enter) — when a drag enters its bounds.
+/// - [`DropTarget::Hover(position, payload)`](crate::events::DropTarget::Hover) — when a drag moves within its bounds.
+/// - [`DropTarget::Leave`](crate::events::DropTarget::Leave) — when a drag leaves its bounds or is cancelled.
+/// - [`DropTarget::Drop(position, payload)`](crate::events::DropTarget::Drop) — when a drag is dropped within its bounds.
+///
+/// All events are passed on to the child.
+#[derive(Debug)]
+pub struct DropTarget<D: Drawable + 'static> {
+    layout: Stack,
+    pub inner: D,
+    hovered: bool,
+}
+
+impl<D: Drawable + 'static> DropTarget<D> {
+    pub fn new(child: D) -> Self {
+        DropTarget{layout: Stack::default(), inner: child, hovered: false}
+    }
+}
+
+impl<D: Drawable + 'static> Component for DropTarget<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.inner as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.inner as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.layout, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.layout, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for DropTarget<D> {
+    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(DragEvent{position, state, payload}) = event.downcast_ref::<DragEvent>() {
+            let mut events: Vec<Box<dyn Event>> = Vec::new();
+            match (state, position) {
+                (DragState::Moved, Some(position)) => {
+                    if !std::mem::replace(&mut self.hovered, true) {
+                        events.push(Box::new(events::DropTarget::Enter(payload.clone())));
+                    }
+                    events.push(Box::new(events::DropTarget::Hover(*position, payload.clone())));
+                },
+                (DragState::Dropped, Some(position)) => {
+                    self.hovered = false;
+                    events.push(Box::new(events::DropTarget::Drop(*position, payload.clone())));
+                },
+                _ if std::mem::take(&mut self.hovered) => events.push(Box::new(events::DropTarget::Leave)),
+                _ => {}
+            }
+            events.push(event);
+            return events;
+        }
+        vec![event]
+    }
 }
 
 #[derive(Debug)]
@@ -334,11 +1047,13 @@ impl<D: Drawable + 'static> OnEvent for Momentum<D> {
                     }, 
                 }
                 self.mouse = *position;
-            } else if event.downcast_ref::<TickEvent>().is_some() && !self.touching {
+            } else if let Some(tick) = event.downcast_ref::<TickEvent>() && !self.touching {
+                //The speed is tuned per frame at 60 frames per second
+                let frames = tick.delta.as_secs_f32() * 60.0;
                 if let Some(time) = self.time {
                     match &mut self.speed {
                         Some(speed) => {
-                            *speed *= 0.92;
+                            *speed *= 0.92f32.powf(frames);
                             if speed.abs() < 0.1 {
                                 self.time = None;
                                 self.speed = None;
@@ -357,7 +1072,7 @@ impl<D: Drawable + 'static> OnEvent for Momentum<D> {
 
                     if let Some(speed) = self.speed {
                         let state = (speed.abs() > 0.01).then_some(
-                            MouseState::Scroll(0.0, speed)
+                            MouseState::Scroll(0.0, speed * frames)
                         );
 
                         if let Some(s) = state {
@@ -372,3 +1087,438 @@ impl<D: Drawable + 'static> OnEvent for Momentum<D> {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{Headless, Probe, ProbeLog};
+    use crate::layouts::{Row, Offset, Size, Padding};
+    use crate::layout::{Area, Layout, SizeRequest};
+    use crate::shortcuts::Shortcut;
+    use crate::events::{RawInput, ElementState, TouchPhase};
+
+    /// Lays its children out in a row.
+    #[derive(Debug)]
+    struct Group(Row, Vec<Box<dyn Drawable>>);
+    impl OnEvent for Group {}
+
+    impl Component for Group {
+        fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {self.1.iter_mut().map(|c| c as &mut dyn Drawable).collect()}
+        fn children(&self) -> Vec<&dyn Drawable> {self.1.iter().map(|c| c as &dyn Drawable).collect()}
+        fn request_size(&self, ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {self.0.request_size(ctx, children)}
+        fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {self.0.build(ctx, size, children)}
+    }
+
+    fn group(children: Vec<Box<dyn Drawable>>) -> Group {
+        Group(Row::new(0.0, Offset::Start, Size::Fit, Padding::default()), children)
+    }
+
+    fn square(size: f32) -> Shape {Shape::new(ShapeType::Rectangle(0.0, (size, size), 0.0), Color(0, 0, 0, 255))}
+
+    fn probe(size: f32) -> (Probe<Shape>, ProbeLog) {
+        let probe = Probe::new(square(size));
+        let log = probe.log();
+        (probe, log)
+    }
+
+    fn app(root: impl Drawable) -> Headless {
+        let mut app = Headless::with_root(Context::headless(None), root, (200, 200), 1.0);
+        app.frame();
+        app
+    }
+
+    fn focusable(tab_index: Option<u32>) -> (Focusable<Probe<Shape>>, uuid::Uuid, ProbeLog) {
+        let (probe, log) = probe(20.0);
+        let mut focusable = Focusable::new(probe);
+        *focusable.tab_index() = tab_index;
+        let id = focusable.id();
+        (focusable, id, log)
+    }
+
+    #[test]
+    fn tab_follows_the_tab_index_and_wraps_around() {
+        let (a, a_id, _) = focusable(None);
+        let (b, b_id, _) = focusable(Some(2));
+        let (c, c_id, _) = focusable(Some(1));
+        let mut app = app(group(vec![Box::new(a), Box::new(b), Box::new(c)]));
+        let shift_tab = Shortcut::parse("Shift+Tab").unwrap();
+
+        for expected in [c_id, b_id, a_id, c_id] {
+            app.press_key(Key::Named(NamedKey::Tab));
+            app.frame();
+            assert!(app.context().is_focused(expected));
+        }
+        for expected in [a_id, b_id] {
+            app.press_shortcut(&shift_tab);
+            app.frame();
+            assert!(app.context().is_focused(expected));
+        }
+    }
+
+    #[test]
+    fn keyboard_events_only_reach_the_focused_subtree() {
+        let (a, _, a_log) = focusable(None);
+        let (b, b_id, b_log) = focusable(None);
+        let mut app = app(group(vec![Box::new(a), Box::new(b)]));
+        //Nothing is focused yet, so every node gets the keys
+        app.press_key(Key::Character("x".into()));
+        app.frame();
+        assert!(a_log.contains("KeyboardEvent") && b_log.contains("KeyboardEvent"));
+        a_log.clear();
+        b_log.clear();
+
+        app.click((30.0, 10.0));
+        app.frame();
+        assert!(app.context().is_focused(b_id));
+        assert!(b_log.contains("Focused(true)"));
+        app.press_key(Key::Character("x".into()));
+        app.frame();
+        assert!(!a_log.contains("KeyboardEvent"));
+        assert_eq!(b_log.count("KeyboardEvent"), 2);
+
+        app.click((10.0, 10.0));
+        app.frame();
+        assert!(b_log.contains("Focused(false)"));
+    }
+
+    #[test]
+    fn text_inputs_only_report_focus_changes() {
+        let (probe, log) = probe(20.0);
+        let mut app = app(group(vec![Box::new(TextInput::new(probe)), Box::new(square(20.0))]));
+        app.click((10.0, 10.0));
+        app.frame();
+        assert_eq!(log.count("Focused(true)"), 1);
+        app.click((10.0, 10.0));
+        app.click((30.0, 10.0));
+        app.frame();
+        assert_eq!(log.count("Focused(true)"), 1);
+        assert_eq!(log.count("Focused(false)"), 1);
+        app.click((30.0, 10.0));
+        app.frame();
+        assert_eq!(log.count("Focused(false)"), 1);
+    }
+
+    #[test]
+    fn captured_sliders_track_the_pointer_outside_their_bounds() {
+        let (other, other_log) = probe(20.0);
+        let (knob, slider_log) = probe(20.0);
+        let mut app = app(group(vec![Box::new(other), Box::new(Slider::new(knob))]));
+        app.move_to((30.0, 10.0));
+        app.input(RawInput::Mouse(ElementState::Pressed));
+        app.frame();
+        assert!(slider_log.contains("Start(10.0)"));
+        assert!(app.context().is_pointer_captured());
+
+        app.move_to((150.0, 150.0));
+        app.frame();
+        assert!(slider_log.contains("Moved(130.0)"));
+        app.move_to((5.0, 5.0));
+        app.frame();
+        assert!(slider_log.contains("Moved(-15.0)"));
+        app.input(RawInput::Mouse(ElementState::Released));
+        app.frame();
+        assert!(!other_log.contains("Some("));
+        assert!(!app.context().is_pointer_captured());
+
+        slider_log.clear();
+        app.move_to((10.0, 10.0));
+        app.frame();
+        assert!(other_log.contains("Some((10.0, 10.0))"));
+        assert!(!slider_log.contains("Moved(") && !slider_log.contains("Some("));
+    }
+
+    fn ms(ms: u64) -> Duration {Duration::from_millis(ms)}
+
+    fn tap(app: &mut Headless, position: (f32, f32)) {
+        app.touch(1, position, TouchPhase::Started);
+        app.touch(1, position, TouchPhase::Ended);
+        app.frame();
+    }
+
+    /// Returns the number following `field` in the last logged event that has it.
+    fn logged_number(log: &ProbeLog, field: &str) -> f32 {
+        let event = log.events().into_iter().rev().find(|e| e.contains(field)).unwrap();
+        let rest = &event[event.find(field).unwrap() + field.len()..];
+        rest[..rest.find([',', ' ', '}', ')']).unwrap()].parse().unwrap()
+    }
+
+    #[test]
+    fn long_presses_fire_after_the_duration_unless_moved() {
+        let (probe, log) = probe(100.0);
+        let mut app = app(LongPress::new(probe, ms(500), 10.0));
+        app.touch(1, (10.0, 10.0), TouchPhase::Started);
+        app.frame();
+        app.touch(1, (15.0, 10.0), TouchPhase::Moved);
+        app.advance(ms(300));
+        assert!(!log.contains("LongPress"));
+        app.advance(ms(300));
+        assert!(log.contains("LongPress((10.0, 10.0))"));
+        app.advance(ms(600));
+        assert_eq!(log.count("LongPress"), 1);
+        app.touch(1, (15.0, 10.0), TouchPhase::Ended);
+
+        app.touch(1, (10.0, 10.0), TouchPhase::Started);
+        app.frame();
+        app.touch(1, (30.0, 10.0), TouchPhase::Moved);
+        app.frame();
+        app.advance(ms(600));
+        assert_eq!(log.count("LongPress"), 1);
+    }
+
+    #[test]
+    fn double_taps_must_fall_within_the_interval() {
+        let (probe, log) = probe(100.0);
+        let mut app = app(DoubleTap::new(probe, ms(300), 20.0));
+        tap(&mut app, (10.0, 10.0));
+        app.advance(ms(200));
+        tap(&mut app, (15.0, 15.0));
+        assert!(log.contains("DoubleTap((15.0, 15.0))"));
+
+        app.advance(ms(400));
+        tap(&mut app, (10.0, 10.0));
+        app.advance(ms(400));
+        tap(&mut app, (10.0, 10.0));
+        assert_eq!(log.count("DoubleTap"), 1);
+        app.advance(ms(100));
+        tap(&mut app, (60.0, 60.0));
+        assert_eq!(log.count("DoubleTap"), 1);
+    }
+
+    #[test]
+    fn swipes_report_their_direction_and_speed() {
+        let (probe, log) = probe(200.0);
+        let mut app = app(Swipe::new(probe, 50.0, 200.0));
+        let swipe = |app: &mut Headless, from: (f32, f32), to: (f32, f32), time: u64| {
+            app.touch(1, from, TouchPhase::Started);
+            app.frame();
+            app.touch(1, to, TouchPhase::Moved);
+            app.touch(1, to, TouchPhase::Ended);
+            app.advance(ms(time));
+        };
+
+        swipe(&mut app, (10.0, 50.0), (150.0, 60.0), 100);
+        assert!(log.contains("direction: Right"));
+        assert!((logged_number(&log, "velocity: (") - 1400.0).abs() < 1.0);
+        swipe(&mut app, (100.0, 190.0), (90.0, 20.0), 100);
+        assert!(log.contains("direction: Up"));
+        //Too slow, then too short
+        swipe(&mut app, (10.0, 50.0), (150.0, 60.0), 1000);
+        swipe(&mut app, (10.0, 50.0), (40.0, 50.0), 10);
+        assert_eq!(log.count("Swipe {"), 2);
+    }
+
+    #[test]
+    fn pinches_report_scale_and_rotation_across_the_wrap() {
+        let (probe, log) = probe(200.0);
+        let mut app = app(Pinch::new(probe));
+        app.touch(1, (150.0, 100.0), TouchPhase::Started);
+        app.touch(2, (50.0, 101.0), TouchPhase::Started);
+        app.frame();
+        assert!(log.contains("Start"));
+
+        //The angle between the pointers goes from just under π to just over -π
+        app.touch(2, (50.0, 99.0), TouchPhase::Moved);
+        app.frame();
+        let rotation = logged_number(&log, "rotation: ");
+        assert!(rotation > 0.0 && rotation < 0.03, "{rotation}");
+        assert!((logged_number(&log, "scale: ") - 1.0).abs() < 1e-3);
+
+        app.touch(2, (100.0, 100.0), TouchPhase::Moved);
+        app.frame();
+        assert!((logged_number(&log, "scale: ") - 0.5).abs() < 0.01);
+        app.touch(1, (150.0, 100.0), TouchPhase::Ended);
+        app.frame();
+        assert!(log.contains("End"));
+    }
+
+    #[test]
+    fn angles_wrap_into_the_half_open_range() {
+        assert!((wrap_angle(TAU - 0.1) + 0.1).abs() < 1e-5);
+        assert!((wrap_angle(-TAU + 0.1) - 0.1).abs() < 1e-5);
+        assert_eq!(wrap_angle(-PI), PI);
+        assert_eq!(wrap_angle(PI), PI);
+        assert_eq!(wrap_angle(0.5), 0.5);
+    }
+
+    /// Logs the [`events::DropTarget`] events it receives, with `u32` payloads read back.
+    #[derive(Debug)]
+    struct Receiver(Stack, Shape, std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl OnEvent for Receiver {
+        fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+            if let Some(target) = event.downcast_ref::<events::DropTarget>() {
+                let value = |payload: &crate::events::Payload| payload.get::<u32>().copied();
+                self.2.lock().unwrap().push(match target {
+                    events::DropTarget::Enter(payload) => format!("Enter {:?}", value(payload)),
+                    events::DropTarget::Hover(position, payload) => format!("Hover {position:?} {:?}", value(payload)),
+                    events::DropTarget::Leave => "Leave".to_string(),
+                    events::DropTarget::Drop(position, payload) => format!("Drop {position:?} {:?}", value(payload)),
+                });
+            }
+            vec![event]
+        }
+    }
+
+    impl Component for Receiver {
+        fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![&mut self.1]}
+        fn children(&self) -> Vec<&dyn Drawable> {vec![&self.1]}
+        fn request_size(&self, ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {self.0.request_size(ctx, children)}
+        fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {self.0.build(ctx, size, children)}
+    }
+
+    /// A draggable carrying `7u32` next to a drop target, both 40 pixels wide.
+    fn drag_and_drop() -> (Headless, ProbeLog, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        let (probe, log) = probe(40.0);
+        let draggable = Draggable::new(probe, 5.0, |_ctx| (Box::new(7u32) as Box<dyn Any>, None));
+        let received = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let target = DropTarget::new(Receiver(Stack::default(), square(40.0), received.clone()));
+        (app(group(vec![Box::new(draggable), Box::new(target)])), log, received)
+    }
+
+    #[test]
+    fn drags_start_past_the_threshold_and_drop_on_targets() {
+        let (mut app, log, received) = drag_and_drop();
+        app.move_to((10.0, 10.0));
+        app.input(RawInput::Mouse(ElementState::Pressed));
+        app.move_to((13.0, 10.0));
+        app.frame();
+        assert!(!app.context().is_dragging());
+        app.move_to((20.0, 10.0));
+        app.frame();
+        assert!(log.contains("Started"));
+        assert_eq!(app.context().drag_payload().and_then(|p| p.get::<u32>()), Some(&7));
+
+        for position in [(60.0, 20.0), (70.0, 20.0), (150.0, 150.0), (60.0, 20.0)] {
+            app.move_to(position);
+            app.frame();
+        }
+        app.input(RawInput::Mouse(ElementState::Released));
+        app.frame();
+        assert_eq!(*received.lock().unwrap(), vec![
+            "Enter Some(7)", "Hover (20.0, 20.0) Some(7)", "Hover (30.0, 20.0) Some(7)", "Leave",
+            "Enter Some(7)", "Hover (20.0, 20.0) Some(7)", "Drop (20.0, 20.0) Some(7)",
+        ]);
+        assert!(log.contains("Ended"));
+        assert!(!app.context().is_dragging());
+    }
+
+    #[test]
+    fn drags_released_outside_a_target_are_not_dropped() {
+        let (mut app, log, received) = drag_and_drop();
+        app.move_to((10.0, 10.0));
+        app.input(RawInput::Mouse(ElementState::Pressed));
+        app.move_to((20.0, 10.0));
+        app.frame();
+        app.move_to((60.0, 20.0));
+        app.frame();
+        app.move_to((150.0, 150.0));
+        app.input(RawInput::Mouse(ElementState::Released));
+        app.frame();
+        assert!(log.contains("Ended"));
+        assert!(!app.context().is_dragging());
+        assert!(!received.lock().unwrap().iter().any(|e| e.starts_with("Drop")));
+        assert_eq!(received.lock().unwrap().last().map(String::as_str), Some("Leave"));
+
+        //A cancelled drag leaves the target it is over
+        received.lock().unwrap().clear();
+        app.move_to((10.0, 10.0));
+        app.input(RawInput::Mouse(ElementState::Pressed));
+        app.move_to((20.0, 10.0));
+        app.frame();
+        app.move_to((60.0, 20.0));
+        app.frame();
+        app.context().cancel_drag();
+        app.frame();
+        assert_eq!(*received.lock().unwrap(), vec!["Enter Some(7)", "Hover (20.0, 20.0) Some(7)", "Leave"]);
+    }
+
+    #[test]
+    fn hover_only_changes_when_the_pointer_crosses_the_bounds() {
+        let (probe, log) = probe(40.0);
+        let mut app = app(group(vec![Box::new(Button::new(probe))]));
+        for position in [(10.0, 10.0), (20.0, 10.0), (30.0, 30.0)] {
+            app.move_to(position);
+            app.frame();
+        }
+        assert_eq!((log.count("Hover(true)"), log.count("Hover(false)")), (1, 0));
+        for position in [(100.0, 10.0), (120.0, 10.0)] {
+            app.move_to(position);
+            app.frame();
+        }
+        assert_eq!((log.count("Hover(true)"), log.count("Hover(false)")), (1, 1));
+        app.move_to((10.0, 10.0));
+        app.frame();
+        assert_eq!((log.count("Hover(true)"), log.count("Hover(false)")), (2, 1));
+    }
+
+    #[test]
+    fn the_cursor_follows_the_component_under_the_pointer() {
+        let mut app = app(group(vec![
+            Box::new(Cursor::new(square(20.0), CursorIcon::Pointer)),
+            Box::new(TextInput::new(square(20.0))),
+            Box::new(square(20.0)),
+        ]));
+        for (position, icon) in [
+            ((10.0, 10.0), CursorIcon::Pointer), ((30.0, 10.0), CursorIcon::Text),
+            ((50.0, 10.0), CursorIcon::Default), ((10.0, 10.0), CursorIcon::Pointer), ((150.0, 150.0), CursorIcon::Default),
+        ] {
+            app.move_to(position);
+            app.frame();
+            assert_eq!(app.cursor(), icon, "at {position:?}");
+        }
+    }
+
+    fn composing() -> (Headless, ProbeLog) {
+        let (probe, log) = probe(100.0);
+        let mut input = TextInput::new(probe);
+        input.measure(20.0, |_ctx, line| line.chars().count() as f32 * 10.0);
+        let mut app = app(input);
+        app.click((90.0, 10.0));
+        app.type_text("ab");
+        app.frame();
+        (app, log)
+    }
+
+    fn last_edit(log: &ProbeLog) -> String {
+        log.events().into_iter().rev().find(|e| e.starts_with("Edited")).unwrap()
+    }
+
+    #[test]
+    fn composed_text_is_shown_underlined_and_committed() {
+        let (mut app, log) = composing();
+        assert!(app.ime_area().is_some());
+        app.input(RawInput::Ime(ImeEvent::Preedit("xy".into(), Some((2, 2)))));
+        app.frame();
+        assert!(last_edit(&log).contains("text: \"abxy\", caret: 4, selection: None, composition: Some(2..4)"));
+        let underline = &app.layout().children[1];
+        assert_eq!((underline.offset, underline.size), ((20.0, 19.0), (20.0, 1.0)));
+
+        app.input(RawInput::Ime(ImeEvent::Commit("xy".into())));
+        app.frame();
+        assert!(last_edit(&log).contains("text: \"abxy\", caret: 4, selection: None, composition: None"));
+        assert_eq!(app.layout().children[1].size, (0.0, 1.0));
+    }
+
+    #[test]
+    fn cancelled_compositions_leave_the_text_unchanged() {
+        let (mut app, log) = composing();
+        app.input(RawInput::Ime(ImeEvent::Preedit("xy".into(), None)));
+        app.input(RawInput::Ime(ImeEvent::Preedit(String::new(), None)));
+        app.frame();
+        assert!(last_edit(&log).contains("text: \"ab\", caret: 2, selection: None, composition: None"));
+
+        app.input(RawInput::Ime(ImeEvent::Preedit("xy".into(), None)));
+        app.input(RawInput::Ime(ImeEvent::Disabled));
+        app.frame();
+        assert!(last_edit(&log).contains("text: \"ab\", caret: 2, selection: None, composition: None"));
+        assert_eq!(app.layout().children[1].size, (0.0, 1.0));
+
+        //Compositions only reach the focused input
+        app.click((150.0, 150.0));
+        app.frame();
+        assert!(app.ime_area().is_none());
+        app.input(RawInput::Ime(ImeEvent::Preedit("xy".into(), None)));
+        app.frame();
+        assert!(!last_edit(&log).contains("xy"));
+    }
+}
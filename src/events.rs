@@ -1,15 +1,30 @@
 use crate::layout::Scale;
 use crate::Context;
 use std::fmt::Debug;
+use std::any::Any;
+use std::rc::Rc;
+use std::time::Duration;
 
-use maverick_os::window::{Input, TouchPhase, ElementState, MouseScrollDelta, Touch};
-pub use maverick_os::window::{NamedKey, Key, SmolStr};
+use maverick_os::window::{Input, Touch};
+pub use maverick_os::window::{NamedKey, Key, SmolStr, TouchPhase, ElementState, MouseScrollDelta};
+pub use cursor_icon::CursorIcon;
 
 use downcast_rs::{Downcast, impl_downcast};
+use serde::{Deserialize, Serialize};
 pub type Events = std::collections::VecDeque<Box<dyn Event>>;
 
 pub trait OnEvent: Debug + Downcast {
     fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {vec![event]}
+
+    /// Called with every event a descendant emitted through [`Context::bubble`],
+    /// after the descendants in between have handled it.
+    ///
+    /// Return the event to keep it bubbling up to the ancestors,
+    /// or `None` to stop propagation.
+    fn on_bubble(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Option<Box<dyn Event>> {Some(event)}
+
+    /// Return `true` to receive a [`PointerEnter`] and a [`PointerLeave`] when the pointer moves over and off this component.
+    fn hoverable(&self) -> bool {false}
 }
 
 type EventChildren = Vec<((f32, f32), (f32, f32))>;
@@ -60,6 +75,10 @@ pub enum KeyboardState {
 /// - `position`: The mouse position at the time of the event.  
 ///   A component receives `Some(position)` only if the event occurred over it;  
 ///   otherwise, it will be `None`.
+///
+/// While the pointer is captured with [`Context::capture_pointer`], every event except
+/// [`MouseState::Pressed`] goes to the capturing component with a position relative to its origin,
+/// even outside of its bounds, and its siblings receive `None`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MouseEvent {
     pub position: Option<(f32, f32)>,
@@ -67,27 +86,65 @@ pub struct MouseEvent {
 }
 
 impl Event for MouseEvent {
+    fn pass(self: Box<Self>, ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        let captured = ctx.pointer_capture.as_ref().filter(|capture| capture.mouse && self.state != MouseState::Pressed);
+        let positions = captured.and_then(|capture| capture_route(ctx, &capture.path, self.position, children))
+            .unwrap_or_else(|| hit_test(self.position, children));
+        positions.into_iter().map(|position|
+            Some(Box::new(MouseEvent{position, state: self.state}) as Box<dyn Event>)
+        ).collect()
+    }
+}
+
+/// # Pointer Enter
+///
+/// `PointerEnter` is received by a component that is [`OnEvent::hoverable`] when a [`MouseEvent`] first reaches it
+/// with a position, meaning the pointer moved over it. It is not passed on to children,
+/// which receive their own `PointerEnter` when the pointer reaches them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerEnter;
+
+impl Event for PointerEnter {
     fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
-        let mut passed = false;
-        children.iter().rev().map(|(offset, size)| { // Reverse to click on the top most element
-            let position = self.position.and_then(|position| (!passed).then(|| (
-                position.0 > offset.0 &&
-                position.0 < offset.0+size.0 &&
-                 position.1 > offset.1 &&
-                position.1 < offset.1+size.1
-                ).then(|| {
-                    passed = true;
-                    (position.0 - offset.0, position.1 - offset.1)
-            })).flatten());
-
-            // let position = self.position.map(|position| {
-            //     if !passed { passed = true; }
-            //     ((position.0 - offset.0).clamp(0.0, size.0),
-            //     (position.1 - offset.1).clamp(0.0, size.1))
-            // });
+        children.iter().map(|_| None).collect()
+    }
+}
 
-            Some(Box::new(MouseEvent{position, state: self.state}) as Box<dyn Event>)
-        }).collect::<Vec<_>>().into_iter().rev().collect()
+/// # Pointer Leave
+///
+/// `PointerLeave` is received by a component that received a [`PointerEnter`]
+/// when a [`MouseEvent`] reaches it without a position, meaning the pointer left it.
+/// It is not passed on to children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerLeave;
+
+impl Event for PointerLeave {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| None).collect()
+    }
+}
+
+/// The modifier keys held during a [`KeyboardEvent`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The Command key on Apple platforms, the Windows or Super key elsewhere.
+    pub meta: bool,
+}
+
+impl Modifiers {
+    /// `true` on Apple platforms, where Command takes the role of Control in shortcuts.
+    pub const APPLE: bool = cfg!(any(target_os = "macos", target_os = "ios"));
+
+    /// Returns `true` if the platform's primary shortcut modifier is held,
+    /// Command on Apple platforms and Control elsewhere.
+    pub fn command(&self) -> bool {
+        match Self::APPLE {
+            true => self.meta,
+            false => self.control,
+        }
     }
 }
 
@@ -96,32 +153,385 @@ impl Event for MouseEvent {
 /// `KeyboardEvent` is triggered whenever the [`KeyboardState`] changes.
 /// 
 /// - `key`: The [`Key`] that triggered the event.
+/// - `modifiers`: The [`Modifiers`] held when the event was triggered.
+/// - `text`: The text produced by the key press, including characters composed with dead keys.
+///
+/// While a node is focused (see [`Context::focus`]), the event is only passed
+/// along the path to that node and to its descendants. Otherwise it is passed to every child.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KeyboardEvent {
     pub key: Key,
     pub state: KeyboardState,
+    pub modifiers: Modifiers,
+    pub text: Option<SmolStr>,
 }
 
 impl Event for KeyboardEvent {
+    fn pass(self: Box<Self>, ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        let route = ctx.focus.route();
+        children.iter().enumerate().map(|(i, _)| match route {
+            Some(child) => (child == Some(i)).then(|| self.clone() as Box<dyn Event>),
+            None => Some(self.clone() as Box<dyn Event>),
+        }).collect()
+    }
+}
+
+/// # Ime Event
+///
+/// `ImeEvent` is triggered by an input method editor, used to compose text
+/// such as Chinese, Japanese or Korean.
+///
+/// Routed like a [`KeyboardEvent`], to the focused node if any.
+/// Preedit cursors are char ranges within the preedit text.
+///
+/// Input methods are not supported by the window yet, maverick_os does not forward
+/// their events. Until it does, `ImeEvent`s only come from [`RawInput::Ime`],
+/// such as in a [`Headless`](crate::headless::Headless) run or a replayed recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImeEvent {
+    /// The input method was enabled.
+    Enabled,
+    /// The text being composed changed, an empty text ends the composition.
+    Preedit(String, Option<(usize, usize)>),
+    /// The composed text was committed and should be inserted.
+    Commit(String),
+    /// The input method was disabled.
+    Disabled,
+}
+
+impl Event for ImeEvent {
+    fn pass(self: Box<Self>, ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        let route = ctx.focus.route();
+        children.iter().enumerate().map(|(i, _)| match route {
+            Some(child) => (child == Some(i)).then(|| self.clone() as Box<dyn Event>),
+            None => Some(self.clone() as Box<dyn Event>),
+        }).collect()
+    }
+}
+
+/// The device behind a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+    /// A stylus. The window only tells pens apart from fingers on platforms that
+    /// report the altitude of the stylus, such as the Apple Pencil on iOS,
+    /// elsewhere they are reported as [`PointerKind::Touch`].
+    Pen,
+}
+
+/// Represents the different states of a pointer in a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerState {
+    /// The pointer was pressed, or touched the screen.
+    Pressed,
+    /// The pointer was moved.
+    Moved,
+    /// The pointer was released, or left the screen.
+    Released,
+    /// The platform cancelled the touch.
+    Cancelled,
+}
+
+/// A pointer that is currently pressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pointer {
+    pub id: u64,
+    pub kind: PointerKind,
+    pub position: (f32, f32),
+}
+
+/// # Pointer Event
+///
+/// `PointerEvent` is triggered whenever a pointer is pressed, moved or released.
+/// Unlike [`MouseEvent`], every finger on the screen gets its own stream of events,
+/// so components can follow several pointers at once.
+///
+/// - `id`: Identifies the pointer between its press and release, the mouse always has the id `0`.
+///   Ids are only unique for a given `kind`.
+/// - `position`: Passed to children like the position of a [`MouseEvent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerEvent {
+    pub id: u64,
+    pub kind: PointerKind,
+    pub position: Option<(f32, f32)>,
+    pub state: PointerState,
+}
+
+impl PointerEvent {
+    fn mouse(position: (f32, f32), state: PointerState) -> Self {
+        PointerEvent{id: 0, kind: PointerKind::Mouse, position: Some(position), state}
+    }
+}
+
+impl Event for PointerEvent {
+    fn pass(self: Box<Self>, ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        let captured = ctx.pointer_capture.as_ref()
+            .filter(|capture| self.state != PointerState::Pressed && capture.pointer == Some((self.kind, self.id)));
+        let positions = captured.and_then(|capture| capture_route(ctx, &capture.path, self.position, children))
+            .unwrap_or_else(|| hit_test(self.position, children));
+        positions.into_iter().map(|position|
+            Some(Box::new(PointerEvent{position, ..*self}) as Box<dyn Event>)
+        ).collect()
+    }
+}
+
+/// The payload carried by a drag, see [`Context::start_drag`].
+#[derive(Clone)]
+pub struct Payload(pub(crate) Rc<dyn Any>);
+
+impl Payload {
+    /// Returns the payload if it is a `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {self.0.downcast_ref()}
+    /// Returns `true` if the payload is a `T`.
+    pub fn is<T: Any>(&self) -> bool {self.0.is::<T>()}
+}
+
+impl Debug for Payload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Payload")
+    }
+}
+
+/// Represents the different states of a drag in a [`DragEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragState {
+    /// The dragged pointer moved.
+    Moved,
+    /// The dragged pointer was released.
+    Dropped,
+    /// The drag was cancelled with [`Context::cancel_drag`].
+    Cancelled,
+}
+
+/// # Drag Event
+///
+/// `DragEvent` is triggered while a drag started with [`Context::start_drag`] is in progress,
+/// whenever the pointer moves and once when it is released.
+///
+/// - `position`: Passed to children like the position of a [`MouseEvent`],
+///   so only the components under the pointer receive `Some(position)`.
+///   Always `None` when the drag was cancelled.
+#[derive(Debug, Clone)]
+pub struct DragEvent {
+    pub position: Option<(f32, f32)>,
+    pub state: DragState,
+    pub payload: Payload,
+}
+
+impl Event for DragEvent {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        hit_test(self.position, children).into_iter().map(|position|
+            Some(Box::new(DragEvent{position, state: self.state, payload: self.payload.clone()}) as Box<dyn Event>)
+        ).collect()
+    }
+}
+
+/// Returns the position relative to each child, or `None` for every child but the top most one under `position`.
+fn hit_test(position: Option<(f32, f32)>, children: &EventChildren) -> Vec<Option<(f32, f32)>> {
+    let mut passed = false;
+    children.iter().rev().map(|(offset, size)| { // Reverse to click on the top most element
+        position.and_then(|position| (!passed).then(|| (
+            position.0 > offset.0 &&
+            position.0 < offset.0+size.0 &&
+            position.1 > offset.1 &&
+            position.1 < offset.1+size.1
+        ).then(|| {
+            passed = true;
+            (position.0 - offset.0, position.1 - offset.1)
+        })).flatten())
+    }).collect::<Vec<_>>().into_iter().rev().collect()
+}
+
+/// A pointer capture started with [`Context::capture_pointer`].
+#[derive(Debug, Clone)]
+pub(crate) struct Capture {
+    /// The index path of the capturing node.
+    pub path: Vec<usize>,
+    /// `true` until the mouse is released.
+    pub mouse: bool,
+    /// The pointer whose [`PointerEvent`]s are captured, until it is released.
+    pub pointer: Option<(PointerKind, u64)>,
+}
+
+impl Capture {
+    /// Returns the pointer released by `event`, `None` for the mouse.
+    pub fn released(event: &dyn Event) -> Option<Option<(PointerKind, u64)>> {
+        if let Some(MouseEvent{state: MouseState::Released, ..}) = event.downcast_ref::<MouseEvent>() {
+            return Some(None);
+        }
+        event.downcast_ref::<PointerEvent>()
+            .filter(|p| matches!(p.state, PointerState::Released | PointerState::Cancelled))
+            .map(|p| Some((p.kind, p.id)))
+    }
+
+    /// Stops capturing the released pointer, returns `true` once nothing is captured anymore.
+    pub fn release(&mut self, released: Option<(PointerKind, u64)>) -> bool {
+        match released {
+            None => self.mouse = false,
+            Some(pointer) if self.pointer == Some(pointer) => self.pointer = None,
+            Some(_) => {},
+        }
+        !self.mouse && self.pointer.is_none()
+    }
+}
+
+/// Passes the position only to the child leading to the capturing node at `target`, relative to that child,
+/// or returns `None` when the current node is not on the way to the target.
+fn capture_route(ctx: &Context, target: &[usize], position: Option<(f32, f32)>, children: &EventChildren) -> Option<Vec<Option<(f32, f32)>>> {
+    let route = ctx.focus.route_to(target)?;
+    Some(children.iter().enumerate().map(|(i, (offset, _))|
+        position.filter(|_| route == Some(i)).map(|position| (position.0 - offset.0, position.1 - offset.1))
+    ).collect())
+}
+
+/// Maps the position of a [`MouseEvent`], [`PointerEvent`] or [`DragEvent`], leaving other events as they are.
+pub(crate) fn map_position(mut event: Box<dyn Event>, map: impl Fn((f32, f32)) -> (f32, f32)) -> Box<dyn Event> {
+    let position = if let Some(e) = event.downcast_mut::<MouseEvent>() {
+        &mut e.position
+    } else if let Some(e) = event.downcast_mut::<PointerEvent>() {
+        &mut e.position
+    } else if let Some(e) = event.downcast_mut::<DragEvent>() {
+        &mut e.position
+    } else {
+        return event;
+    };
+    *position = position.map(map);
+    event
+}
+
+/// Returns the position of an event positioned by the pointer, see [`is_pointer`].
+pub(crate) fn pointer_position(event: &dyn Event) -> Option<(f32, f32)> {
+    if let Some(e) = event.downcast_ref::<MouseEvent>() {
+        e.position
+    } else if let Some(e) = event.downcast_ref::<PointerEvent>() {
+        e.position
+    } else {
+        event.downcast_ref::<DragEvent>().and_then(|e| e.position)
+    }
+}
+
+/// Returns `true` for the events positioned by the pointer, the ones [`map_position`] maps.
+pub(crate) fn is_pointer(event: &dyn Event) -> bool {
+    event.downcast_ref::<MouseEvent>().is_some()
+        || event.downcast_ref::<PointerEvent>().is_some()
+        || event.downcast_ref::<DragEvent>().is_some()
+}
+
+/// # Command Event
+///
+/// `CommandEvent` is triggered when a [`Shortcut`](crate::shortcuts::Shortcut) registered
+/// with [`Context::register_shortcut`] is pressed, instead of the [`KeyboardEvent`].
+///
+/// - `command`: The name the shortcut was registered with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandEvent {
+    pub command: String,
+}
+
+impl Event for CommandEvent {
     fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
         children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
     }
 }
+
+/// # Focus Event
+///
+/// `FocusEvent` is triggered whenever the keyboard focus moves,
+/// either through [`Context::focus`] and [`Context::blur`] or through Tab and Shift+Tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusEvent {
+    /// The node with this id received the focus.
+    Gained(uuid::Uuid),
+    /// The node with this id lost the focus.
+    Lost(uuid::Uuid),
+}
+
+impl Event for FocusEvent {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(Box::new(*self) as Box<dyn Event>)).collect()
+    }
+}
 /// # Tick Event
 ///
 /// `TickEvent` is emitted on every tick and can be used to perform continuous or repeated actions.
+///
+/// - `delta`: The time elapsed since the previous tick, use it to animate at the same speed at any frame rate.
 #[derive(Debug, Clone, Copy)]
-pub struct TickEvent;
+pub struct TickEvent {
+    pub delta: Duration,
+}
 impl Event for TickEvent {
     fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
         children.iter().map(|_| Some(Box::new(*self) as Box<dyn Event>)).collect()
     }
 }
 
+/// Window input in a platform independent form.
+///
+/// Positions are in physical pixels, like the input received from the window.
+/// `RawInput` can be constructed directly to feed synthetic input into
+/// a [`Headless`](crate::headless::Headless) run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawInput {
+    /// A finger or pen touched, moved on or left the screen.
+    ///
+    /// Window input is reported as [`PointerKind::Touch`] unless the platform identifies a pen.
+    Touch { id: u64, kind: PointerKind, position: (f32, f32), phase: TouchPhase },
+    /// The cursor moved to a new position.
+    CursorMoved((f32, f32)),
+    /// A mouse button was pressed or released.
+    Mouse(ElementState),
+    /// The mouse wheel or touchpad was scrolled.
+    MouseWheel { delta: MouseScrollDelta, phase: TouchPhase },
+    /// A key was pressed or released, producing `text` if any.
+    Keyboard { key: Key, state: ElementState, text: Option<SmolStr> },
+    /// The held modifier keys changed, the [`KeyboardEvent`]s that follow carry them.
+    Modifiers(Modifiers),
+    /// An input method editor composed or committed text.
+    Ime(ImeEvent),
+    /// The window gained or lost focus, losing focus releases the held modifiers.
+    Focused(bool),
+    /// A new frame is about to be drawn.
+    Tick,
+}
+
+impl RawInput {
+    /// Converts window input, returning `None` for input roost does not handle.
+    pub fn from_input(input: Input) -> Option<Self> {
+        match input {
+            Input::Touch(Touch { location, phase, id, force, .. }) => Some(RawInput::Touch{
+                //The force type is not exported by the platform layer, only a stylus has an altitude
+                id, kind: match force.is_some_and(|f| format!("{f:?}").contains("altitude_angle: Some")) {
+                    true => PointerKind::Pen,
+                    false => PointerKind::Touch,
+                },
+                position: (location.x as f32, location.y as f32), phase
+            }),
+            Input::CursorMoved{position, ..} => Some(RawInput::CursorMoved((position.0 as f32, position.1 as f32))),
+            Input::Mouse{state, ..} => Some(RawInput::Mouse(state)),
+            Input::MouseWheel{delta, phase, ..} => Some(RawInput::MouseWheel{delta, phase}),
+            Input::Keyboard{event, ..} => Some(RawInput::Keyboard{key: event.logical_key, state: event.state, text: event.text}),
+            Input::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                Some(RawInput::Modifiers(Modifiers{
+                    shift: state.shift_key(), control: state.control_key(), alt: state.alt_key(), meta: state.super_key()
+                }))
+            },
+            Input::Focused(focused) => Some(RawInput::Focused(focused)),
+            Input::Tick => Some(RawInput::Tick),
+            _ => None
+        }
+    }
+}
+
 pub(crate) struct EventHandler {
     touching: bool,
     mouse: (f32, f32),
     scroll: Option<(f32, f32)>,
+    primary: Option<(PointerKind, u64)>,
+    pointers: Vec<Pointer>,
+    modifiers: Modifiers,
 }
 
 impl EventHandler {
@@ -129,58 +539,97 @@ impl EventHandler {
         touching: false,
         mouse: (0.0, 0.0),
         scroll: None,
+        primary: None,
+        pointers: Vec::new(),
+        modifiers: Modifiers::default(),
     }}
 
-    pub fn on_input(&mut self, scale: &Scale, input: Input) -> Option<Box<dyn Event>> {
+    pub fn on_input(&mut self, scale: &Scale, input: Input) -> Vec<Box<dyn Event>> {
+        RawInput::from_input(input).map(|input| self.on_raw_input(scale, input)).unwrap_or_default()
+    }
+
+    /// Returns the pointers that are currently pressed.
+    pub fn pointers(&self) -> &[Pointer] {&self.pointers}
+
+    /// Converts input into a [`PointerEvent`] followed by a [`MouseEvent`].
+    ///
+    /// Only the first finger or pen on the screen drives the [`MouseEvent`]s,
+    /// every finger and pen gets its own stream of [`PointerEvent`]s.
+    pub fn on_raw_input(&mut self, scale: &Scale, input: RawInput) -> Vec<Box<dyn Event>> {
         match input {
-            Input::Touch(Touch { location, phase, .. }) => {
-                let location = (location.x as f32, location.y as f32);
+            RawInput::Touch { id, kind, position: location, phase } => {
                 let position = (scale.logical(location.0), scale.logical(location.1));
-                let event = match phase {
+                let pointer = Pointer{id, kind, position};
+                let state = match phase {
                     TouchPhase::Started => {
-                        self.scroll = Some(position);
-                        self.touching = true;
-                        Some(MouseState::Pressed)
-                    },
-                    TouchPhase::Ended | TouchPhase::Cancelled => {
-                        self.touching = false;
-                        Some(MouseState::Released)
+                        self.pointers.retain(|p| p.id != id || p.kind != kind);
+                        self.pointers.push(pointer);
+                        PointerState::Pressed
                     },
                     TouchPhase::Moved => {
-                        self.scroll.and_then(|(prev_x, prev_y)| {
-                            self.scroll = Some(position);
-                            let dx = position.0 - prev_x;
-                            let dy = position.1 - prev_y;
-                            let scroll_x = -dx * 1.0;
-                            let scroll_y = -dy * 1.0;
-                    
-                            (scroll_x.abs() > 0.01 || scroll_y.abs() > 0.01).then_some(
-                                MouseState::Scroll(scroll_x, scroll_y)
-                            )
-                        })
-                    }
-                }.map(|state| Box::new(MouseEvent{position: Some(position), state}) as Box<dyn Event>);
+                        if let Some(p) = self.pointers.iter_mut().find(|p| p.id == id && p.kind == kind) {
+                            p.position = position;
+                        }
+                        PointerState::Moved
+                    },
+                    TouchPhase::Ended => {
+                        self.pointers.retain(|p| p.id != id || p.kind != kind);
+                        PointerState::Released
+                    },
+                    TouchPhase::Cancelled => {
+                        self.pointers.retain(|p| p.id != id || p.kind != kind);
+                        PointerState::Cancelled
+                    },
+                };
+
+                if phase == TouchPhase::Started && self.primary.is_none() {
+                    self.primary = Some((kind, id));
+                }
+                let mouse = (self.primary == Some((kind, id))).then(|| self.on_primary_touch(position, phase)).flatten();
+                if matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled) && self.primary == Some((kind, id)) {
+                    self.primary = None;
+                }
+
+                let pointer = PointerEvent{id, kind, position: Some(position), state};
+                std::iter::once(Box::new(pointer) as Box<dyn Event>).chain(mouse).collect()
+            },
+            RawInput::CursorMoved(position) => {
+                let position = (scale.logical(position.0), scale.logical(position.1));
+                if self.mouse == position { return Vec::new(); }
                 self.mouse = position;
-                event
-            },                
-            Input::CursorMoved{position, ..} => {
-                let position = (scale.logical(position.0 as f32), scale.logical(position.1 as f32));
-                (self.mouse != position).then_some({
-                    self.mouse = position;
-                    Box::new(MouseEvent{position: Some(position), state: MouseState::Moved})
-                })
+                if let Some(p) = self.pointers.iter_mut().find(|p| p.kind == PointerKind::Mouse) {
+                    p.position = position;
+                }
+                crate::events![
+                    PointerEvent::mouse(position, PointerState::Moved),
+                    MouseEvent{position: Some(position), state: MouseState::Moved}
+                ]
             },
-            Input::Mouse{state, ..} => {
-                Some(Box::new(MouseEvent{position: Some(self.mouse), state: match state {
-                    ElementState::Pressed => MouseState::Pressed,
-                    ElementState::Released => MouseState::Released,
-                }}))
+            RawInput::Mouse(state) => {
+                let pointer = PointerEvent::mouse(self.mouse, match state {
+                    ElementState::Pressed => {
+                        self.pointers.retain(|p| p.kind != PointerKind::Mouse);
+                        self.pointers.push(Pointer{id: 0, kind: PointerKind::Mouse, position: self.mouse});
+                        PointerState::Pressed
+                    },
+                    ElementState::Released => {
+                        self.pointers.retain(|p| p.kind != PointerKind::Mouse);
+                        PointerState::Released
+                    },
+                });
+                crate::events![
+                    pointer,
+                    MouseEvent{position: Some(self.mouse), state: match state {
+                        ElementState::Pressed => MouseState::Pressed,
+                        ElementState::Released => MouseState::Released,
+                    }}
+                ]
             },
-            Input::MouseWheel{delta, phase, ..} => {
+            RawInput::MouseWheel{delta, phase} => {
                 match phase {
                     TouchPhase::Started => {
                         self.scroll = Some((0.0, 0.0));
-                        None
+                        Vec::new()
                     }
                     TouchPhase::Moved => {
                         self.scroll.map(|(prev_x, prev_y)| {
@@ -192,23 +641,66 @@ impl EventHandler {
                             let scroll_x = prev_x + (-pos.0 * 0.2);
                             let scroll_y = prev_y + (-pos.1 * 0.2);
 
-                            Some(Box::new(MouseEvent{position: Some(self.mouse), state: MouseState::Scroll(scroll_x, scroll_y)}) as Box<dyn Event>)
-                        })?
+                            crate::events![MouseEvent{position: Some(self.mouse), state: MouseState::Scroll(scroll_x, scroll_y)}]
+                        }).unwrap_or_default()
                     },
                     // TouchPhase::Ended => None,
-                    _ => None
+                    _ => Vec::new()
                 }
             },
-            Input::Keyboard{event, ..} => {
-                Some(Box::new(KeyboardEvent{
-                    key: event.logical_key, state: match event.state {
-                    ElementState::Pressed => KeyboardState::Pressed,
-                    ElementState::Released => KeyboardState::Released,
-                }}))
+            RawInput::Keyboard{key, state, text} => {
+                crate::events![KeyboardEvent{
+                    key, state: match state {
+                        ElementState::Pressed => KeyboardState::Pressed,
+                        ElementState::Released => KeyboardState::Released,
+                    },
+                    modifiers: self.modifiers,
+                    text,
+                }]
             },
-            _ => None
+            RawInput::Modifiers(modifiers) => {
+                self.modifiers = modifiers;
+                Vec::new()
+            },
+            RawInput::Ime(ime) => crate::events![ime],
+            RawInput::Focused(focused) => {
+                //Key releases are not reported to an unfocused window
+                if !focused {self.modifiers = Modifiers::default();}
+                Vec::new()
+            },
+            RawInput::Tick => Vec::new()
         }
     }
+
+    /// Converts the first finger on the screen into a [`MouseEvent`].
+    fn on_primary_touch(&mut self, position: (f32, f32), phase: TouchPhase) -> Option<Box<dyn Event>> {
+        let event = match phase {
+            TouchPhase::Started => {
+                self.scroll = Some(position);
+                self.touching = true;
+                Some(MouseState::Pressed)
+            },
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touching = false;
+                Some(MouseState::Released)
+            },
+            TouchPhase::Moved => {
+                self.scroll.and_then(|(prev_x, prev_y)| {
+                    self.scroll = Some(position);
+                    let dx = position.0 - prev_x;
+                    let dy = position.1 - prev_y;
+                    let scroll_x = -dx * 1.0;
+                    let scroll_y = -dy * 1.0;
+            
+                    (scroll_x.abs() > 0.01 || scroll_y.abs() > 0.01).then_some(
+                        MouseState::Scroll(scroll_x, scroll_y)
+                    )
+                })
+            }
+        }.map(|state| Box::new(MouseEvent{position: Some(position), state}) as Box<dyn Event>);
+        self.mouse = position;
+        event
+    }
 }
 
 #[macro_export]
@@ -260,11 +752,27 @@ impl Event for Slider {
     }
 }
 
+/// Events emitted by the [`Focusable`](crate::emitters::Focusable) emmiter object.
+#[derive(Debug, Clone, Copy)]
+pub enum Focusable {
+    Focused(bool),
+}
+
+impl Event for Focusable {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
 /// Events emitted by the [`TextInput`](crate::emitters::TextInput) emmiter object.
 #[derive(Debug, Clone)]
 pub enum TextInput {
     Hover(bool),
     Focused(bool),
+    /// The text, caret or selection changed.
+    Edited(crate::editor::TextState),
+    /// Enter was pressed in a single line input.
+    Submitted(String),
 }
 
 impl Event for TextInput {
@@ -273,3 +781,203 @@ impl Event for TextInput {
     }
 }
 
+/// Emitted by the [`LongPress`](crate::emitters::LongPress) emmiter object
+/// with the position where the pointer was held.
+#[derive(Debug, Clone, Copy)]
+pub struct LongPress(pub (f32, f32));
+
+impl Event for LongPress {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Emitted by the [`DoubleTap`](crate::emitters::DoubleTap) emmiter object
+/// with the position of the second tap.
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleTap(pub (f32, f32));
+
+impl Event for DoubleTap {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// The direction of a [`Swipe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Emitted by the [`Swipe`](crate::emitters::Swipe) emmiter object.
+///
+/// - `velocity`: The average velocity of the swipe in logical pixels per second.
+#[derive(Debug, Clone, Copy)]
+pub struct Swipe {
+    pub direction: SwipeDirection,
+    pub velocity: (f32, f32),
+}
+
+impl Event for Swipe {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Events emitted by the [`Pinch`](crate::emitters::Pinch) emmiter object.
+#[derive(Debug, Clone, Copy)]
+pub enum Pinch {
+    /// A second pointer was pressed.
+    Start,
+    /// The pointers moved.
+    ///
+    /// - `scale`: The distance between the pointers relative to their distance at the start.
+    /// - `rotation`: The rotation of the pointers since the start, in radians within (-π, π].
+    /// - `center`: The point halfway between the pointers.
+    Changed { scale: f32, rotation: f32, center: (f32, f32) },
+    /// One of the pointers was released.
+    End,
+}
+
+impl Event for Pinch {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Events emitted by the [`Draggable`](crate::emitters::Draggable) emmiter object.
+#[derive(Debug, Clone, Copy)]
+pub enum Draggable {
+    /// The pointer moved past the threshold and the drag started.
+    Started,
+    /// The pointer was released and the drag ended.
+    Ended,
+}
+
+impl Event for Draggable {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Events emitted by the [`DropTarget`](crate::emitters::DropTarget) emmiter object.
+#[derive(Debug, Clone)]
+pub enum DropTarget {
+    /// A drag entered the target.
+    Enter(Payload),
+    /// A drag moved over the target.
+    Hover((f32, f32), Payload),
+    /// A drag left the target, or was cancelled while over it.
+    Leave,
+    /// A drag was dropped on the target.
+    Drop((f32, f32), Payload),
+}
+
+impl Event for DropTarget {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &Vec<((f32, f32), (f32, f32))>) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawable::{Shape, ShapeType, Color};
+    use crate::headless::{Headless, Probe, ProbeLog};
+
+    fn app() -> (Headless, ProbeLog) {
+        let probe = Probe::new(Shape::new(ShapeType::Rectangle(0.0, (100.0, 100.0), 0.0), Color(0, 0, 0, 255)));
+        let log = probe.log();
+        (Headless::with_root(Context::headless(None), probe, (100, 100), 1.0), log)
+    }
+
+    fn touch(id: u64, kind: PointerKind, position: (f32, f32), phase: TouchPhase) -> RawInput {
+        RawInput::Touch{id, kind, position, phase}
+    }
+
+    #[test]
+    fn every_pointer_is_tracked_by_kind_and_id() {
+        let (mut app, log) = app();
+        app.touch(1, (10.0, 10.0), TouchPhase::Started);
+        app.input(touch(1, PointerKind::Pen, (20.0, 20.0), TouchPhase::Started));
+        app.touch(2, (30.0, 30.0), TouchPhase::Started);
+        app.touch(2, (35.0, 40.0), TouchPhase::Moved);
+        assert_eq!(app.pointers(), [
+            Pointer{id: 1, kind: PointerKind::Touch, position: (10.0, 10.0)},
+            Pointer{id: 1, kind: PointerKind::Pen, position: (20.0, 20.0)},
+            Pointer{id: 2, kind: PointerKind::Touch, position: (35.0, 40.0)},
+        ]);
+
+        app.input(touch(1, PointerKind::Pen, (20.0, 20.0), TouchPhase::Ended));
+        app.touch(2, (35.0, 40.0), TouchPhase::Cancelled);
+        assert_eq!(app.pointers(), [Pointer{id: 1, kind: PointerKind::Touch, position: (10.0, 10.0)}]);
+        app.frame();
+        assert!(log.contains("PointerEvent { id: 1, kind: Pen, position: Some((20.0, 20.0)), state: Pressed }"));
+        assert!(log.contains("PointerEvent { id: 2, kind: Touch, position: Some((35.0, 40.0)), state: Cancelled }"));
+    }
+
+    #[test]
+    fn only_the_primary_touch_drives_the_mouse() {
+        let (mut app, log) = app();
+        app.touch(1, (10.0, 10.0), TouchPhase::Started);
+        app.touch(2, (50.0, 50.0), TouchPhase::Started);
+        app.touch(2, (60.0, 60.0), TouchPhase::Moved);
+        app.touch(1, (10.0, 30.0), TouchPhase::Moved);
+        app.touch(1, (10.0, 30.0), TouchPhase::Ended);
+        app.touch(2, (70.0, 70.0), TouchPhase::Moved);
+        app.frame();
+        let mouse = log.events().into_iter().filter(|e| e.starts_with("MouseEvent")).collect::<Vec<_>>();
+        assert_eq!(mouse, vec![
+            "MouseEvent { position: Some((10.0, 10.0)), state: Pressed }",
+            "MouseEvent { position: Some((10.0, 30.0)), state: Scroll(-0.0, -20.0) }",
+            "MouseEvent { position: Some((10.0, 30.0)), state: Released }",
+        ]);
+
+        //A new finger becomes the primary one once the first has lifted
+        app.touch(2, (70.0, 70.0), TouchPhase::Ended);
+        app.input(touch(2, PointerKind::Pen, (40.0, 40.0), TouchPhase::Started));
+        app.frame();
+        assert!(log.contains("MouseEvent { position: Some((40.0, 40.0)), state: Pressed }"));
+    }
+
+    fn key(key: Key, state: ElementState) -> RawInput {RawInput::Keyboard{key, state, text: None}}
+
+    #[test]
+    fn modifiers_follow_the_platform_state() {
+        let (mut app, log) = app();
+        let shift = Modifiers{shift: true, ..Modifiers::default()};
+        //Both Shift keys are held, releasing one keeps Shift held
+        app.input(key(Key::Named(NamedKey::Shift), ElementState::Pressed));
+        app.input(RawInput::Modifiers(shift));
+        app.input(key(Key::Named(NamedKey::Shift), ElementState::Pressed));
+        app.input(key(Key::Named(NamedKey::Shift), ElementState::Released));
+        app.input(key(Key::Character("a".into()), ElementState::Pressed));
+        app.frame();
+        assert!(log.events().last().unwrap().contains("shift: true"));
+
+        //Modifiers already held when the window is focused are reported without a key press
+        app.input(RawInput::Focused(false));
+        app.input(RawInput::Focused(true));
+        app.input(RawInput::Modifiers(Modifiers{control: true, ..Modifiers::default()}));
+        app.input(key(Key::Character("a".into()), ElementState::Pressed));
+        app.frame();
+        assert!(log.events().last().unwrap().contains("shift: false, control: true"));
+    }
+
+    #[test]
+    fn shortcuts_match_the_held_modifiers() {
+        let (mut app, log) = app();
+        let save = crate::shortcuts::Shortcut::parse("Ctrl+S").unwrap();
+        app.context().register_shortcut(save, "save", None).unwrap();
+        app.input(key(Key::Character("s".into()), ElementState::Pressed));
+        app.frame();
+        assert!(!log.contains("CommandEvent"));
+        app.input(RawInput::Modifiers(Modifiers{control: true, ..Modifiers::default()}));
+        app.input(key(Key::Character("s".into()), ElementState::Pressed));
+        app.frame();
+        assert_eq!(log.count("CommandEvent { command: \"save\" }"), 1);
+    }
+}
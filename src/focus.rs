@@ -0,0 +1,110 @@
+use uuid::Uuid;
+
+/// Tracks the focused node and the focusable nodes of the last layout pass.
+///
+/// Nodes are identified by the path of child indices from the root,
+/// which is followed while building and while dispatching events
+/// along with the offset of each node from the root.
+#[derive(Debug, Default)]
+pub(crate) struct Focus {
+    pub focused: Option<Uuid>,
+    nodes: Vec<(Uuid, Option<u32>, Vec<usize>)>,
+    path: Vec<usize>,
+    origins: Vec<(f32, f32)>,
+    scopes: Vec<(Uuid, Vec<usize>)>,
+}
+
+impl Focus {
+    /// Descends into the child at `index`, placed at `offset` from the current node.
+    pub fn enter(&mut self, index: usize, offset: (f32, f32)) {
+        let origin = self.origin();
+        self.path.push(index);
+        self.origins.push((origin.0 + offset.0, origin.1 + offset.1));
+    }
+
+    /// Returns to the parent node.
+    pub fn exit(&mut self) {
+        self.path.pop();
+        self.origins.pop();
+    }
+
+    /// Returns the offset of the current node from the root, in logical pixels.
+    pub fn origin(&self) -> (f32, f32) {
+        self.origins.last().copied().unwrap_or_default()
+    }
+
+    /// Forgets the focusable nodes and scopes before a new layout pass registers them again.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.scopes.clear();
+    }
+
+    /// Registers the current node as focusable.
+    pub fn register(&mut self, id: Uuid, tab_index: Option<u32>) {
+        self.nodes.push((id, tab_index, self.path.clone()));
+    }
+
+    /// Registers the current node as a scope for shortcuts.
+    pub fn register_scope(&mut self, id: Uuid) {
+        self.scopes.push((id, self.path.clone()));
+    }
+
+    /// Returns the path of the scope or focusable node `id`.
+    pub fn scope_path(&self, id: Uuid) -> Option<&Vec<usize>> {
+        self.scopes.iter().map(|(i, path)| (i, path))
+            .chain(self.nodes.iter().map(|(i, _, path)| (i, path)))
+            .find(|(i, _)| **i == id).map(|(_, path)| path)
+    }
+
+    /// Returns the depth of the scope `id` if the focused node is within it.
+    pub fn scope_depth(&self, id: Uuid) -> Option<usize> {
+        let scope = self.scope_path(id)?;
+        self.focused_path().filter(|focused| focused.starts_with(scope)).map(|_| scope.len())
+    }
+
+    fn focused_path(&self) -> Option<&Vec<usize>> {
+        self.focused.and_then(|id| self.nodes.iter().find(|(n, ..)| *n == id).map(|(.., path)| path))
+    }
+
+    /// Returns the path of the node currently being built or handling an event.
+    pub fn path(&self) -> &[usize] {&self.path}
+
+    /// Returns which children of the current node should receive keyboard input:
+    /// `None` for all of them, otherwise the index of the child leading to the focused node, if any.
+    pub fn route(&self) -> Option<Option<usize>> {
+        let focused = self.focused_path()?;
+        self.route_to(focused)
+    }
+
+    /// Returns which children of the current node lead to the node at `target`:
+    /// `None` if the current node is the target or one of its descendants,
+    /// otherwise the index of the child leading to the target, if any.
+    pub fn route_to(&self, target: &[usize]) -> Option<Option<usize>> {
+        match target.starts_with(&self.path) {
+            true if target.len() > self.path.len() => Some(Some(target[self.path.len()])),
+            _ if self.path.starts_with(target) => None,
+            _ => Some(None),
+        }
+    }
+
+    /// Returns the node after the focused one in tab order, or before it when `reverse` is set.
+    ///
+    /// Nodes with a tab index come first, in ascending order, followed by the others in tree order.
+    pub fn next(&self, reverse: bool) -> Option<Uuid> {
+        let mut order = self.nodes.iter().map(|(id, index, _)| (*id, *index)).collect::<Vec<_>>();
+        order.sort_by_key(|(_, index)| match index {
+            Some(i) => (0, *i),
+            None => (1, 0),
+        });
+        let len = order.len();
+        if len == 0 { return None; }
+        let current = self.focused.and_then(|id| order.iter().position(|(n, _)| *n == id));
+        let next = match (current, reverse) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        Some(order[next].0)
+    }
+}
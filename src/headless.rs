@@ -0,0 +1,446 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Wake, Waker};
+use std::time::Duration;
+
+use crate::wgpu::Canvas;
+use crate::drawable::{Drawable, LayoutNode};
+use crate::events::{OnEvent, Event, TickEvent, RawInput, ElementState, Key, Pointer, PointerKind, TouchPhase, CursorIcon, Modifiers, NamedKey, SmolStr};
+use crate::shortcuts::Shortcut;
+use crate::layout::{Area, Layout, Scale, SizeRequest};
+use crate::layouts::Stack;
+use crate::recording::Recording;
+use crate::{Application, Component, Context, Root, Window};
+
+/// The time every [`Headless::frame`] advances by, so headless runs animate deterministically.
+pub const FRAME_DELTA: Duration = Duration::from_micros(16_667);
+
+/// Runs a [`Drawable`] tree without a window.
+///
+/// `Headless` follows the same pipeline as the windowed engine: input is turned into
+/// events, events are dispatched, and the tree is laid out and drawn once per frame.
+/// Frames can be rendered into an offscreen texture and read back with [`Headless::render`].
+///
+///```rust,no_run
+/// # use roost_ui::{Application, Context};
+/// # use roost_ui::drawable::{Drawable, Shape, ShapeType, Color};
+/// # use roost_ui::headless::{Headless, block_on};
+/// # struct MyApp;
+/// # impl Application for MyApp {
+/// #     async fn new(_ctx: &mut Context) -> impl Drawable {
+/// #         Shape::new(ShapeType::Rectangle(0.0, (10.0, 10.0), 0.0), Color(0, 0, 0, 255))
+/// #     }
+/// # }
+/// let mut app = block_on(Headless::new::<MyApp>((800, 600), 1.0));
+/// let frame = app.render();
+/// frame.save("frame.png").unwrap();
+///```
+pub struct Headless {
+    root: Root,
+    size: (u32, u32),
+    canvas: Option<Canvas>,
+}
+
+impl Headless {
+    /// Starts the [`Application`] `A` with the given physical size and scale factor.
+    ///
+    /// Plugins are registered, [`Application::new`] is awaited and the first layout is built,
+    /// just like when the app is launched through [`start!`](crate::start).
+    pub async fn new<A: Application>(size: (u32, u32), scale_factor: f64) -> Self {
+        let mut context = Context::headless(None);
+        let plugins = A::plugins(&mut context);
+        context.plugins = plugins.into_iter().map(|p| ((*p).type_id(), p)).collect();
+        let application = A::new(&mut context).await;
+        Self::with_root(context, application, size, scale_factor)
+    }
+
+    /// Runs `root` as the root drawable with the given physical size and scale factor.
+    ///
+    /// The `context` should be the one used to load the fonts and images of `root`.
+    pub fn with_root(context: Context, root: impl Drawable, size: (u32, u32), scale_factor: f64) -> Self {
+        let scale = Scale(scale_factor);
+        let screen = (scale.logical(size.0 as f32), scale.logical(size.1 as f32));
+        Headless {
+            root: Root::new(context, Box::new(root), scale, screen),
+            size,
+            canvas: None,
+        }
+    }
+
+    /// Returns a mutable reference to the [`Context`].
+    pub fn context(&mut self) -> &mut Context {&mut self.root.context}
+
+    /// Returns the logical size of the screen.
+    pub fn screen(&self) -> (f32, f32) {self.root.screen}
+
+    /// Resizes the screen to the given physical size and scale factor.
+    pub fn resize(&mut self, size: (u32, u32), scale_factor: f64) {
+        self.size = match &mut self.canvas {
+            Some(canvas) => canvas.resize::<Arc<Window>>(None, size.0, size.1),
+            None => size,
+        };
+        self.root.resize(scale_factor, self.size);
+    }
+
+    /// Queues input to be dispatched on the next frame.
+    ///
+    /// Positions are in physical pixels, use [`Headless::move_to`], [`Headless::click`],
+    /// [`Headless::touch`] and [`Headless::press_key`] to work with logical positions instead.
+    pub fn input(&mut self, input: RawInput) {
+        self.root.raw_input(input);
+    }
+
+    /// Moves the cursor to the logical `position`.
+    pub fn move_to(&mut self, position: (f32, f32)) {
+        let position = (self.root.scale.physical(position.0), self.root.scale.physical(position.1));
+        self.input(RawInput::CursorMoved(position));
+    }
+
+    /// Moves the cursor to the logical `position`, then presses and releases the mouse button.
+    pub fn click(&mut self, position: (f32, f32)) {
+        self.move_to(position);
+        self.input(RawInput::Mouse(ElementState::Pressed));
+        self.input(RawInput::Mouse(ElementState::Released));
+    }
+
+    /// Moves the finger `id` to the logical `position` in the given `phase`.
+    pub fn touch(&mut self, id: u64, position: (f32, f32), phase: TouchPhase) {
+        let position = (self.root.scale.physical(position.0), self.root.scale.physical(position.1));
+        self.input(RawInput::Touch{id, kind: PointerKind::Touch, position, phase});
+    }
+
+    /// Returns the pointers that are pressed after the input queued so far.
+    pub fn pointers(&self) -> &[Pointer] {
+        self.root.pointers()
+    }
+
+    /// Returns the cursor icon the windowed engine would show after the last frame.
+    pub fn cursor(&self) -> CursorIcon {
+        self.root.cursor()
+    }
+
+    /// Returns the caret area reported by the focused text input after the last frame, see [`Context::set_ime_area`].
+    pub fn ime_area(&self) -> Option<((f32, f32), (f32, f32))> {
+        self.root.ime_area()
+    }
+
+    /// Presses and releases `key`.
+    ///
+    /// Character keys and Space produce their text, like they would on a keyboard.
+    pub fn press_key(&mut self, key: Key) {
+        let text = match &key {
+            Key::Character(c) => Some(c.clone()),
+            Key::Named(NamedKey::Space) => Some(SmolStr::new(" ")),
+            _ => None,
+        };
+        self.input(RawInput::Keyboard{key: key.clone(), state: ElementState::Pressed, text});
+        self.input(RawInput::Keyboard{key, state: ElementState::Released, text: None});
+    }
+
+    /// Types `text` one character at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let key = match c {
+                ' ' => Key::Named(NamedKey::Space),
+                c => Key::Character(SmolStr::new(c.to_string())),
+            };
+            self.press_key(key);
+        }
+    }
+
+    /// Holds the modifiers of `shortcut` while pressing and releasing its key.
+    pub fn press_shortcut(&mut self, shortcut: &Shortcut) {
+        let Modifiers{shift, control, alt, meta} = shortcut.modifiers;
+        let modifiers = [(shift, NamedKey::Shift), (control, NamedKey::Control), (alt, NamedKey::Alt), (meta, NamedKey::Super)]
+            .into_iter().filter(|(held, _)| *held).map(|(_, key)| Key::Named(key)).collect::<Vec<_>>();
+        for key in &modifiers {
+            self.input(RawInput::Keyboard{key: key.clone(), state: ElementState::Pressed, text: None});
+        }
+        self.input(RawInput::Modifiers(shortcut.modifiers));
+        self.press_key(shortcut.key.clone());
+        for key in modifiers.into_iter().rev() {
+            self.input(RawInput::Keyboard{key, state: ElementState::Released, text: None});
+        }
+        self.input(RawInput::Modifiers(Modifiers::default()));
+    }
+
+    /// Replays `recording` to the end, running one frame per recorded frame.
+    pub fn replay(&mut self, recording: Recording) {
+        self.root.context.replay(recording);
+        while self.root.context.is_replaying() {
+            self.frame();
+        }
+    }
+
+    /// Runs a single frame without rendering it, advancing the time by [`FRAME_DELTA`].
+    pub fn frame(&mut self) {
+        self.advance(FRAME_DELTA);
+    }
+
+    /// Runs a single frame without rendering it, advancing the time by `delta`.
+    pub fn advance(&mut self, delta: Duration) {
+        self.root.frame(Some(delta));
+    }
+
+    /// Returns the geometry tree computed by the last frame.
+    pub fn layout(&self) -> LayoutNode {
+        self.root.layout()
+    }
+
+    /// Runs a single frame and renders it offscreen, advancing the time by [`FRAME_DELTA`].
+    ///
+    /// The offscreen canvas is created on the first call, preferring a hardware
+    /// adapter and falling back to a software one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no wgpu adapter is available, see [`Headless::try_render`].
+    pub fn render(&mut self) -> image::RgbaImage {
+        self.try_render().expect("No wgpu adapter available for headless rendering")
+    }
+
+    /// Like [`Headless::render`], but returns `None` instead of panicking when no wgpu adapter
+    /// is available, so tests can skip rendering on machines without one.
+    /// The frame still runs either way.
+    pub fn try_render(&mut self) -> Option<image::RgbaImage> {
+        let items = self.root.frame(Some(FRAME_DELTA));
+        if self.canvas.is_none() {
+            self.canvas = Some(block_on(Canvas::offscreen(self.size.0, self.size.1))?.0);
+        }
+        let canvas = self.canvas.as_mut()?;
+        canvas.draw(&mut self.root.context.assets.atlas, items);
+        canvas.read()
+    }
+}
+
+/// The [`Probe`] wraps a drawable and records every event that reaches it,
+/// except [`TickEvent`]s, so tests can inspect what a component received.
+///
+/// Events are recorded by their [`Debug`] representation and are passed on to the child unchanged.
+///
+///```rust
+/// # use roost_ui::Context;
+/// # use roost_ui::drawable::{Shape, ShapeType, Color};
+/// # use roost_ui::emitters::Button;
+/// # use roost_ui::headless::{Headless, Probe};
+/// # let shape = Shape::new(ShapeType::Rectangle(0.0, (50.0, 50.0), 0.0), Color(0, 0, 0, 255));
+/// let probe = Probe::new(shape);
+/// let log = probe.log();
+/// let mut app = Headless::with_root(Context::headless(None), Button::new(probe), (100, 100), 1.0);
+/// app.click((10.0, 10.0));
+/// app.frame();
+/// assert!(log.contains("Pressed(true)"));
+///```
+#[derive(Debug)]
+pub struct Probe<D: Drawable + 'static>(Stack, pub D, ProbeLog);
+impl<D: Drawable + 'static> Probe<D> {
+    pub fn new(child: D) -> Self {Probe(Stack::default(), child, ProbeLog::default())}
+
+    /// Returns a handle to the events recorded by this probe.
+    pub fn log(&self) -> ProbeLog {self.2.clone()}
+}
+
+impl<D: Drawable + 'static> Component for Probe<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {
+        Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
+        Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for Probe<D> {
+    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.downcast_ref::<TickEvent>().is_none() {
+            self.2.0.lock().unwrap().push(format!("{event:?}"));
+        }
+        vec![event]
+    }
+}
+
+/// A shared handle to the events recorded by a [`Probe`].
+#[derive(Debug, Clone, Default)]
+pub struct ProbeLog(Arc<Mutex<Vec<String>>>);
+
+impl ProbeLog {
+    /// Returns the recorded events in the order they were received.
+    pub fn events(&self) -> Vec<String> {self.0.lock().unwrap().clone()}
+
+    /// Returns `true` if any recorded event contains `pattern`.
+    pub fn contains(&self, pattern: &str) -> bool {self.count(pattern) > 0}
+
+    /// Returns the number of recorded events that contain `pattern`.
+    pub fn count(&self, pattern: &str) -> usize {
+        self.0.lock().unwrap().iter().filter(|e| e.contains(pattern)).count()
+    }
+
+    /// Forgets all recorded events.
+    pub fn clear(&self) {self.0.lock().unwrap().clear()}
+}
+
+/// Compares rendered drawables against a reference PNG.
+///
+/// The drawable is laid out at a logical `size`, rendered at `scale_factor`
+/// and compared pixel by pixel. Each color channel may differ from the reference
+/// by at most `tolerance` before the pixel counts as mismatched.
+///
+/// When the `UPDATE_SNAPSHOTS` environment variable is set, the rendered image is written
+/// as the new reference instead. A missing reference is otherwise an error, so a typo in the
+/// path cannot make a check pass; the rendered image is written to `<name>.actual.png` to inspect.
+/// On a mismatch, `<name>.actual.png` and `<name>.diff.png` are written next to the reference,
+/// with mismatched pixels highlighted in red on the diff.
+///
+///```rust,no_run
+/// # use roost_ui::Context;
+/// # use roost_ui::drawable::{Shape, ShapeType, Color};
+/// # use roost_ui::headless::Snapshot;
+/// let card = Shape::new(ShapeType::RoundedRectangle(0.0, (200.0, 48.0), 8.0, 0.0), Color(255, 0, 0, 255));
+/// Snapshot::new("tests/snapshots/card.png", (200.0, 48.0), 2.0, 2).assert(Context::headless(None), card);
+///```
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub size: (f32, f32),
+    pub scale_factor: f64,
+    pub tolerance: u8,
+}
+
+impl Snapshot {
+    pub fn new(path: impl Into<PathBuf>, size: (f32, f32), scale_factor: f64, tolerance: u8) -> Self {
+        Snapshot{path: path.into(), size, scale_factor, tolerance}
+    }
+
+    /// Lays out and renders `drawable` at this snapshot's size and scale factor.
+    pub fn render(&self, ctx: Context, drawable: impl Drawable) -> image::RgbaImage {
+        let size = (
+            (self.size.0 as f64 * self.scale_factor).round() as u32,
+            (self.size.1 as f64 * self.scale_factor).round() as u32
+        );
+        Headless::with_root(ctx, drawable, size, self.scale_factor).render()
+    }
+
+    /// Renders `drawable` and compares it with the reference image.
+    pub fn check(&self, ctx: Context, drawable: impl Drawable) -> Result<(), SnapshotError> {
+        self.compare(&self.render(ctx, drawable))
+    }
+
+    /// Renders `drawable` and panics if it does not match the reference image.
+    pub fn assert(&self, ctx: Context, drawable: impl Drawable) {
+        if let Err(error) = self.check(ctx, drawable) {
+            panic!("Snapshot {:?} failed: {error}", self.path);
+        }
+    }
+
+    /// Compares an already `rendered` image with the reference image.
+    pub fn compare(&self, rendered: &image::RgbaImage) -> Result<(), SnapshotError> {
+        let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+        if update || !self.path.exists() {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if update { return Ok(rendered.save(&self.path)?); }
+            rendered.save(self.sibling("actual"))?;
+            return Err(SnapshotError::Missing(self.path.clone()));
+        }
+
+        let expected = image::open(&self.path)?.into_rgba8();
+        if expected.dimensions() != rendered.dimensions() {
+            rendered.save(self.sibling("actual"))?;
+            return Err(SnapshotError::Size{expected: expected.dimensions(), actual: rendered.dimensions()});
+        }
+
+        let mut mismatched = 0;
+        let diff = image::RgbaImage::from_fn(rendered.width(), rendered.height(), |x, y| {
+            let (e, a) = (expected.get_pixel(x, y), rendered.get_pixel(x, y));
+            match e.0.iter().zip(a.0).any(|(e, a)| e.abs_diff(a) > self.tolerance) {
+                true => {
+                    mismatched += 1;
+                    image::Rgba([255, 0, 0, 255])
+                },
+                false => {
+                    let l = ((a[0] as u16 + a[1] as u16 + a[2] as u16) / 6) as u8;
+                    image::Rgba([l, l, l, 255])
+                }
+            }
+        });
+
+        if mismatched == 0 { return Ok(()); }
+        let diff_path = self.sibling("diff");
+        diff.save(&diff_path)?;
+        rendered.save(self.sibling("actual"))?;
+        Err(SnapshotError::Mismatch{pixels: mismatched, diff: diff_path})
+    }
+
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let stem = self.path.file_stem().and_then(|s| s.to_str()).unwrap_or("snapshot");
+        self.path.with_file_name(format!("{stem}.{suffix}.png"))
+    }
+}
+
+/// Reasons a [`Snapshot`] comparison can fail.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The reference does not exist. Set `UPDATE_SNAPSHOTS` to write it.
+    Missing(PathBuf),
+    /// The rendered image and the reference have different dimensions.
+    Size { expected: (u32, u32), actual: (u32, u32) },
+    /// Some pixels differ by more than the tolerance. `diff` is the path of the written diff image.
+    Mismatch { pixels: usize, diff: PathBuf },
+    /// The reference could not be read or an output image could not be written.
+    Image(image::ImageError),
+    /// A snapshot directory could not be created.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Missing(path) => write!(f, "no reference at {}, run with UPDATE_SNAPSHOTS=1 to write it", path.display()),
+            SnapshotError::Size{expected, actual} => write!(f, "expected a {}x{} image but rendered {}x{}", expected.0, expected.1, actual.0, actual.1),
+            SnapshotError::Mismatch{pixels, diff} => write!(f, "{pixels} pixels differ, see {}", diff.display()),
+            SnapshotError::Image(error) => write!(f, "{error}"),
+            SnapshotError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<image::ImageError> for SnapshotError {
+    fn from(error: image::ImageError) -> Self {SnapshotError::Image(error)}
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(error: std::io::Error) -> Self {SnapshotError::Io(error)}
+}
+
+/// Runs a future to completion on the current thread.
+///
+/// Useful for awaiting [`Headless::new`] from synchronous tests.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = TaskContext::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use super::Context;
 use wgpu_canvas::{Area as CanvasArea, Item as CanvasItem, Text, Shape};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Area {
     pub offset: (f32, f32),
     pub size: (f32, f32)
@@ -43,13 +43,45 @@ impl SizeRequest {
     /// Returns the maximum height.
     pub fn max_height(&self) -> f32 { self.max_height }
 
-    /// Creates a new `SizeRequest`, panicking if min > max for either dimension.
+    /// Creates a new `SizeRequest`.
+    ///
+    /// Invalid requests, such as min > max, are reported when a component returns them,
+    /// see [`Context::set_strict_layout`]. Use [`SizeRequest::try_new`] to check them up front.
     pub fn new(min_width: f32, min_height: f32, max_width: f32, max_height: f32) -> Self {
-        if min_width > max_width { panic!("Min Width was Greater Than Max Width"); }
-        if min_height > max_height { panic!("Min Height was Greater Than Max Height"); }
         SizeRequest { min_width, min_height, max_width, max_height }
     }
 
+    /// Creates a new `SizeRequest`, returning an error if it is invalid.
+    pub fn try_new(min_width: f32, min_height: f32, max_width: f32, max_height: f32) -> Result<Self, LayoutError> {
+        let request = SizeRequest { min_width, min_height, max_width, max_height };
+        request.validate().map(|_| request)
+    }
+
+    /// Checks that all sizes are positive numbers and that min <= max for both dimensions.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        let sizes = [self.min_width, self.min_height, self.max_width, self.max_height];
+        if sizes.iter().any(|s| s.is_nan() || *s < 0.0) {
+            return Err(LayoutError::InvalidSize(*self));
+        }
+        if self.min_width > self.max_width || self.min_height > self.max_height {
+            return Err(LayoutError::MinGreaterThanMax(*self));
+        }
+        Ok(())
+    }
+
+    /// Returns a valid request, replacing negative or NaN sizes with zero
+    /// and raising each maximum to at least its minimum.
+    pub fn sanitize(&self) -> SizeRequest {
+        let clean = |s: f32| if s.is_nan() {0.0} else {s.max(0.0)};
+        let (min_width, min_height) = (clean(self.min_width), clean(self.min_height));
+        SizeRequest {
+            min_width,
+            min_height,
+            max_width: clean(self.max_width).max(min_width),
+            max_height: clean(self.max_height).max(min_height),
+        }
+    }
+
     /// Creates a fixed-size `SizeRequest` where min and max are equal.
     pub fn fixed(size: (f32, f32)) -> Self {
         SizeRequest { min_width: size.0, min_height: size.1, max_width: size.0, max_height: size.1 }
@@ -83,9 +115,9 @@ impl SizeRequest {
         SizeRequest::new(self.min_width, self.min_height + h, self.max_width, self.max_height + h)
     }
 
-    /// Returns a new request with height decreased.
+    /// Returns a new request with height decreased, never below zero.
     pub fn remove_height(&self, h: f32) -> SizeRequest {
-        SizeRequest::new(self.min_width, self.min_height - h, self.max_width, self.max_height - h)
+        SizeRequest::new(self.min_width, (self.min_height - h).max(0.0), self.max_width, (self.max_height - h).max(0.0))
     }
 
     /// Returns the combined maximum of two requests.
@@ -99,12 +131,51 @@ impl SizeRequest {
     }
 }
 
+/// Problems found in a [`SizeRequest`] or an [`Area`] returned by a layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayoutError {
+    /// A minimum size is greater than the matching maximum size.
+    MinGreaterThanMax(SizeRequest),
+    /// A requested size is negative or NaN.
+    InvalidSize(SizeRequest),
+    /// An allotted area has a negative or NaN size.
+    InvalidArea(Area),
+}
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::MinGreaterThanMax(r) => write!(f, "min size ({}, {}) is greater than max size ({}, {})", r.min_width, r.min_height, r.max_width, r.max_height),
+            LayoutError::InvalidSize(r) => write!(f, "size request {r:?} contains a negative or NaN size"),
+            LayoutError::InvalidArea(a) => write!(f, "area {a:?} has a negative or NaN size"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl Area {
+    /// Checks that the size of the area is a positive number.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        match [self.size.0, self.size.1].iter().any(|s| s.is_nan() || *s < 0.0) {
+            true => Err(LayoutError::InvalidArea(*self)),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns the area with negative or NaN sizes replaced with zero.
+    pub fn sanitize(&self) -> Area {
+        let clean = |s: f32| if s.is_nan() {0.0} else {s.max(0.0)};
+        Area{offset: self.offset, size: (clean(self.size.0), clean(self.size.1))}
+    }
+}
+
 /// A simple stack layout that overlays children on top of each other.
 #[derive(Debug, Clone, Copy)]
 pub struct DefaultStack;
 impl Layout for DefaultStack {
     fn request_size(&self, _ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {
-        children.into_iter().reduce(|c, o| c.max(&o)).unwrap()
+        children.into_iter().reduce(|c, o| c.max(&o)).unwrap_or_default()
     }
 
     fn build(&self, _ctx: &mut Context, size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
@@ -128,7 +199,7 @@ pub(crate) trait Scaling {
     fn scale(self, scale: &Scale) -> Self;
 
     fn scale_text(mut text: Text, scale: &Scale) -> Text {
-        text.scale = scale.0 as f32;
+        text.scale *= scale.0 as f32;
         text
     }
 
@@ -170,3 +241,74 @@ impl Scaling for CanvasArea {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Component, Context};
+    use crate::drawable::Drawable;
+    use crate::events::OnEvent;
+
+    /// Requests a negative width and a NaN height.
+    #[derive(Debug)]
+    struct Broken;
+    impl OnEvent for Broken {}
+
+    impl Component for Broken {
+        fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![]}
+        fn children(&self) -> Vec<&dyn Drawable> {vec![]}
+        fn request_size(&self, _ctx: &mut Context, _children: Vec<SizeRequest>) -> SizeRequest {
+            SizeRequest::new(-10.0, f32::NAN, 20.0, 30.0)
+        }
+        fn build(&mut self, _ctx: &mut Context, _size: (f32, f32), _children: Vec<SizeRequest>) -> Vec<Area> {vec![]}
+    }
+
+    #[test]
+    fn invalid_requests_are_reported() {
+        let nan = SizeRequest::new(0.0, f32::NAN, 10.0, 10.0);
+        assert!(matches!(nan.validate(), Err(LayoutError::InvalidSize(_))));
+        let flipped = SizeRequest::new(20.0, 0.0, 10.0, 10.0);
+        assert_eq!(flipped.validate(), Err(LayoutError::MinGreaterThanMax(flipped)));
+        assert!(SizeRequest::try_new(0.0, -1.0, 10.0, 10.0).is_err());
+        assert!(SizeRequest::try_new(0.0, 0.0, 10.0, 10.0).is_ok());
+        let area = Area{offset: (-5.0, -5.0), size: (10.0, -1.0)};
+        assert_eq!(area.validate(), Err(LayoutError::InvalidArea(area)));
+        assert!(Area{offset: (-5.0, -5.0), size: (0.0, 0.0)}.validate().is_ok());
+    }
+
+    #[test]
+    fn sanitizing_clamps_to_a_valid_request() {
+        assert_eq!(SizeRequest::new(-10.0, f32::NAN, 20.0, -5.0).sanitize(), SizeRequest::new(0.0, 0.0, 20.0, 0.0));
+        assert_eq!(SizeRequest::new(30.0, 0.0, 10.0, 10.0).sanitize(), SizeRequest::new(30.0, 0.0, 30.0, 10.0));
+        let area = Area{offset: (-5.0, 3.0), size: (f32::NAN, -1.0)}.sanitize();
+        assert_eq!(area, Area{offset: (-5.0, 3.0), size: (0.0, 0.0)});
+    }
+
+    #[test]
+    fn remove_height_stops_at_zero() {
+        let request = SizeRequest::new(10.0, 10.0, 50.0, 40.0);
+        assert_eq!(request.remove_height(15.0), SizeRequest::new(10.0, 0.0, 50.0, 25.0));
+        assert_eq!(request.remove_height(100.0), SizeRequest::new(10.0, 0.0, 50.0, 0.0));
+        assert!(request.remove_height(100.0).validate().is_ok());
+    }
+
+    #[test]
+    fn lenient_layouts_sanitize_invalid_sizes() {
+        let ctx = Context::headless(None);
+        assert_eq!(ctx.check_request(|| "Broken".into(), SizeRequest::new(-10.0, f32::NAN, 20.0, 30.0)), SizeRequest::new(0.0, 0.0, 20.0, 30.0));
+        assert_eq!(ctx.check_area(|| "Broken".into(), Area{offset: (0.0, 0.0), size: (-1.0, 5.0)}).size, (0.0, 5.0));
+
+        let mut app = crate::headless::Headless::with_root(ctx, Broken, (100, 100), 1.0);
+        app.frame();
+        assert_eq!(app.layout().size, (20.0, 30.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid layout in")]
+    fn strict_layouts_panic_on_invalid_sizes() {
+        let mut ctx = Context::headless(None);
+        ctx.set_strict_layout(true);
+        let mut app = crate::headless::Headless::with_root(ctx, Broken, (100, 100), 1.0);
+        app.frame();
+    }
+}
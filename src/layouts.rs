@@ -1,11 +1,14 @@
-use crate::events::{OnEvent, Event};
-use crate::drawable::{Drawable};
+use crate::events::{OnEvent, Event, MouseEvent, MouseState, TickEvent, is_pointer, map_position};
+use crate::drawable::{Drawable, _Drawable, Effect, RequestBranch, SizedBranch, LayoutNode};
+use crate::animation::{Animation, Transition, Edge};
 use crate::layout::{Layout, Area, SizeRequest};
 use crate::{Context, Component};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+use wgpu_canvas::{Area as CanvasArea, Item as CanvasItem};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
@@ -36,6 +39,43 @@ impl Offset {
     }
 }
 
+/// Distribution of leftover space along the main axis of a [`Row`] or [`Column`].
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Justify {
+    /// Items are packed at the start.
+    #[default]
+    Start,
+    /// Items are packed in the center.
+    Center,
+    /// Items are packed at the end.
+    End,
+    /// The first and last items touch the edges, the space is shared between the items.
+    SpaceBetween,
+    /// Each item gets equal space on both sides, so the edges get half the space between items.
+    SpaceAround,
+    /// The edges and the space between items are all equal.
+    SpaceEvenly,
+}
+
+impl Justify {
+    /// Returns the offset of the first item and the extra space between items,
+    /// given the leftover space and the number of items.
+    pub fn get(&self, leftover: f32, count: usize) -> (f32, f32) {
+        let leftover = leftover.max(0.0);
+        let n = count as f32;
+        match self {
+            Self::Start => (0.0, 0.0),
+            Self::Center => (leftover / 2.0, 0.0),
+            Self::End => (leftover, 0.0),
+            Self::SpaceBetween if count > 1 => (0.0, leftover / (n - 1.0)),
+            Self::SpaceBetween => (0.0, 0.0),
+            Self::SpaceAround if count > 0 => (leftover / n / 2.0, leftover / n),
+            Self::SpaceAround => (0.0, 0.0),
+            Self::SpaceEvenly => (leftover / (n + 1.0), leftover / (n + 1.0)),
+        }
+    }
+}
+
 type CustomFunc = dyn Fn(Vec<(f32, f32)>) -> (f32, f32);
 type FitFunc = fn(Vec<(f32, f32)>) -> (f32, f32);
 
@@ -61,7 +101,7 @@ impl Size {
     pub fn get(&self, items: Vec<(f32, f32)>, fit: FitFunc) -> (f32, f32) {
         match self {
             Size::Fit => fit(items),
-            Size::Fill => (items.iter().fold(f32::MIN, |a, b| a.max(b.0)), f32::MAX),
+            Size::Fill => (items.iter().fold(0.0, |a: f32, b| a.max(b.0)), f32::MAX),
             Size::Static(s) => (*s, *s),
             Size::Custom(f) => f(items)
         }
@@ -100,7 +140,7 @@ impl Padding {
     pub fn adjust_size(&self, size: (f32, f32)) -> (f32, f32) {
         let wp = self.0+self.2;
         let hp = self.1+self.3;
-        (size.0-wp, size.1-hp)
+        ((size.0-wp).max(0.0), (size.1-hp).max(0.0))
     }
 
     pub fn adjust_offset(&self, offset: (f32, f32)) -> (f32, f32) {
@@ -119,7 +159,7 @@ pub struct UniformExpand;
 impl UniformExpand {
     pub fn get(sizes: Vec<(f32, f32)>, max_size: f32, spacing: f32) -> Vec<f32> {
         // Calculate the total spacing and the minimum size required
-        let spacing = (sizes.len() - 1) as f32 * spacing;
+        let spacing = sizes.len().saturating_sub(1) as f32 * spacing;
         let min_size = sizes.iter().fold(0.0, |s, i| s + i.0) + spacing;
 
         let mut sizes = sizes.into_iter().map(|s| (s.0, s.1)).collect::<Vec<_>>();
@@ -171,6 +211,90 @@ impl UniformExpand {
     }
 }
 
+/// Growth and shrink behaviour of a child in a [`Row`] or [`Column`].
+///
+/// The child starts at its `basis` size, or its minimum size when there is none.
+/// Free space is shared between the children in proportion to their `grow` weights.
+/// When the children overflow, they give up space in proportion to `shrink` times their basis.
+/// Children are always kept within the minimum and maximum of their [`SizeRequest`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flex {
+    pub grow: f32,
+    pub shrink: f32,
+    pub basis: Option<f32>,
+}
+
+impl Default for Flex {
+    fn default() -> Self {
+        Flex{grow: 1.0, shrink: 1.0, basis: None}
+    }
+}
+
+impl Flex {
+    pub fn new(grow: f32, shrink: f32, basis: Option<f32>) -> Self {
+        Flex{grow, shrink, basis}
+    }
+
+    /// Distributes `max_size` between items with the given `(min, max)` sizes and flex factors.
+    pub fn get(sizes: Vec<(f32, f32)>, flex: Vec<Flex>, max_size: f32, spacing: f32) -> Vec<f32> {
+        let spacing = spacing * sizes.len().saturating_sub(1) as f32;
+        let mut result = sizes.iter().zip(&flex).map(|((min, max), f)|
+            f.basis.unwrap_or(*min).clamp(*min, *max)
+        ).collect::<Vec<_>>();
+
+        let mut free = max_size - spacing - result.iter().sum::<f32>();
+        let growing = free > 0.0;
+        let mut frozen = vec![false; result.len()];
+        loop {
+            let weights = result.iter().zip(&flex).enumerate().map(|(i, (size, f))| match frozen[i] {
+                true => 0.0,
+                false if growing => f.grow,
+                false => f.shrink * f.basis.unwrap_or(*size),
+            }).collect::<Vec<_>>();
+            let total = weights.iter().sum::<f32>();
+            if total <= 0.0 || free.abs() < 0.01 { break; }
+
+            // Clamp the items that would pass their limit and hand the rest to the others.
+            let mut clamped = false;
+            for (i, weight) in weights.iter().enumerate().filter(|(_, w)| **w > 0.0) {
+                let target = result[i] + free * weight / total;
+                let limit = if growing {sizes[i].1} else {sizes[i].0};
+                if (growing && target >= limit) || (!growing && target <= limit) {
+                    free -= limit - result[i];
+                    result[i] = limit;
+                    frozen[i] = true;
+                    clamped = true;
+                }
+            }
+
+            if !clamped {
+                weights.iter().enumerate().for_each(|(i, weight)| result[i] += free * weight / total);
+                break;
+            }
+        }
+        result
+    }
+
+    /// Returns the `(min, max)` size an item with the given `(min, max)` size can take with these factors.
+    ///
+    /// An item that does not grow or shrink is held at its basis in that direction.
+    fn limits(&self, (min, max): (f32, f32)) -> (f32, f32) {
+        let start = self.basis.unwrap_or(min).clamp(min, max);
+        (
+            if self.shrink > 0.0 {min} else {start},
+            if self.grow > 0.0 {max} else {start},
+        )
+    }
+
+    /// Applies the flex factors of each child to its `(min, max)` size, see [`Flex::limits`].
+    fn requests(flex: &BTreeMap<usize, Flex>, sizes: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+        match flex.is_empty() {
+            true => sizes,
+            false => sizes.into_iter().enumerate().map(|(i, size)| flex.get(&i).copied().unwrap_or_default().limits(size)).collect(),
+        }
+    }
+}
+
 /// Horizontal layout of items.
 ///
 /// <img src="https://raw.githubusercontent.com/ramp-stack/pelican_ui_std/main/src/examples/row.png"
@@ -181,11 +305,11 @@ impl UniformExpand {
 /// let layout = Row::new(24.0, Offset::Center, Size::Fit, Padding::new(8.0));
 ///```
 #[derive(Debug, Default)]
-pub struct Row(f32, Offset, Size, Padding);
+pub struct Row(f32, Offset, Size, Padding, BTreeMap<usize, Flex>, Justify);
 
 impl Row {
     pub fn new(spacing: f32, offset: Offset, size: Size, padding: Padding) -> Self {
-        Row(spacing, offset, size, padding)
+        Row(spacing, offset, size, padding, BTreeMap::new(), Justify::Start)
     }
 
     pub fn center(spacing: f32) -> Self {
@@ -201,6 +325,16 @@ impl Row {
     }
 
     pub fn padding(&mut self) -> &mut Padding {&mut self.3}
+
+    /// Returns the [`Justify`] mode used to distribute leftover space between the items.
+    pub fn justify(&mut self) -> &mut Justify {&mut self.5}
+
+    /// Sets the [`Flex`] factors of the child at index `child`.
+    ///
+    /// Once any child has flex factors, the others use [`Flex::default`].
+    pub fn flex(&mut self, child: usize, flex: Flex) {
+        self.4.insert(child, flex);
+    }
 }
 
 impl Layout for Row {
@@ -208,8 +342,8 @@ impl Layout for Row {
         let (widths, heights): (Vec<_>, Vec<_>) = children.into_iter().map(|i|
             ((i.min_width(), i.max_width()), (i.min_height(), i.max_height()))
         ).unzip();
-        let spacing = self.0*(widths.len()-1) as f32;
-        let width = Size::add(widths);
+        let spacing = self.0*widths.len().saturating_sub(1) as f32;
+        let width = Size::add(Flex::requests(&self.4, widths));
         let height = self.2.get(heights, Size::max);
         self.3.adjust_request(SizeRequest::new(width.0, height.0, width.1, height.1).add_width(spacing))
     }
@@ -217,13 +351,18 @@ impl Layout for Row {
     fn build(&self, _ctx: &mut Context, row_size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
         let row_size = self.3.adjust_size(row_size);
 
-        let widths = UniformExpand::get(children.iter().map(|i| (i.min_width(), i.max_width())).collect::<Vec<_>>(), row_size.0, self.0);
+        let sizes = children.iter().map(|i| (i.min_width(), i.max_width())).collect::<Vec<_>>();
+        let widths = match self.4.is_empty() {
+            true => UniformExpand::get(sizes, row_size.0, self.0),
+            false => Flex::get(sizes, (0..children.len()).map(|i| self.4.get(&i).copied().unwrap_or_default()).collect(), row_size.0, self.0),
+        };
 
-        let mut offset = 0.0;
-        children.into_iter().zip(widths).map(|(i, width)| {
-            let size = i.get((width, row_size.1));
+        let sizes = children.into_iter().zip(widths).map(|(i, width)| i.get((width, row_size.1))).collect::<Vec<_>>();
+        let used = sizes.iter().map(|s| s.0).sum::<f32>() + self.0*sizes.len().saturating_sub(1) as f32;
+        let (mut offset, gap) = self.5.get(row_size.0 - used, sizes.len());
+        sizes.into_iter().map(|size| {
             let off = self.3.adjust_offset((offset, self.1.get(row_size.1, size.1)));
-            offset += size.0+self.0;
+            offset += size.0+self.0+gap;
             Area{offset: off, size}
         }).collect()
     }
@@ -239,27 +378,37 @@ impl Layout for Row {
 /// let layout = Column::new(24.0, Offset::Center, Size::Fit, Padding::new(8.0));
 ///```
 #[derive(Debug, Default)]
-pub struct Column(f32, Offset, Size, Padding);
+pub struct Column(f32, Offset, Size, Padding, BTreeMap<usize, Flex>, Justify);
 
 impl Column {
     pub fn new(spacing: f32, offset: Offset, size: Size, padding: Padding) -> Self {
-        Column(spacing, offset, size, padding)
+        Column(spacing, offset, size, padding, BTreeMap::new(), Justify::Start)
     }
 
     pub fn center(spacing: f32) -> Self {
-        Column(spacing, Offset::Center, Size::Fit, Padding::default())
+        Column::new(spacing, Offset::Center, Size::Fit, Padding::default())
     }
 
     pub fn start(spacing: f32) -> Self {
-        Column(spacing, Offset::Start, Size::Fit, Padding::default())
+        Column::new(spacing, Offset::Start, Size::Fit, Padding::default())
     }
 
     pub fn end(spacing: f32) -> Self {
-        Column(spacing, Offset::End, Size::Fit, Padding::default())
+        Column::new(spacing, Offset::End, Size::Fit, Padding::default())
     }
 
 
     pub fn padding(&mut self) -> &mut Padding {&mut self.3}
+
+    /// Returns the [`Justify`] mode used to distribute leftover space between the items.
+    pub fn justify(&mut self) -> &mut Justify {&mut self.5}
+
+    /// Sets the [`Flex`] factors of the child at index `child`.
+    ///
+    /// Once any child has flex factors, the others use [`Flex::default`].
+    pub fn flex(&mut self, child: usize, flex: Flex) {
+        self.4.insert(child, flex);
+    }
 }
 
 impl Layout for Column {
@@ -267,22 +416,27 @@ impl Layout for Column {
         let (widths, heights): (Vec<_>, Vec<_>) = children.into_iter().map(|i|
             ((i.min_width(), i.max_width()), (i.min_height(), i.max_height()))
         ).unzip();
-        let spacing = self.0*(heights.len()-1) as f32;
+        let spacing = self.0*heights.len().saturating_sub(1) as f32;
         let width = self.2.get(widths, Size::max);
-        let height = Size::add(heights);
+        let height = Size::add(Flex::requests(&self.4, heights));
         self.3.adjust_request(SizeRequest::new(width.0, height.0, width.1, height.1).add_height(spacing))
     }
 
     fn build(&self, _ctx: &mut Context, col_size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
         let col_size = self.3.adjust_size(col_size);
 
-        let heights = UniformExpand::get(children.iter().map(|i| (i.min_height(), i.max_height())).collect::<Vec<_>>(), col_size.1, self.0);
+        let sizes = children.iter().map(|i| (i.min_height(), i.max_height())).collect::<Vec<_>>();
+        let heights = match self.4.is_empty() {
+            true => UniformExpand::get(sizes, col_size.1, self.0),
+            false => Flex::get(sizes, (0..children.len()).map(|i| self.4.get(&i).copied().unwrap_or_default()).collect(), col_size.1, self.0),
+        };
 
-        let mut offset = 0.0;
-        children.into_iter().zip(heights).map(|(i, height)| {
-            let size = i.get((col_size.0, height));
+        let sizes = children.into_iter().zip(heights).map(|(i, height)| i.get((col_size.0, height))).collect::<Vec<_>>();
+        let used = sizes.iter().map(|s| s.1).sum::<f32>() + self.0*sizes.len().saturating_sub(1) as f32;
+        let (mut offset, gap) = self.5.get(col_size.1 - used, sizes.len());
+        sizes.into_iter().map(|size| {
             let off = self.3.adjust_offset((self.1.get(col_size.0, size.0), offset));
-            offset += size.1+self.0;
+            offset += size.1+self.0+gap;
             Area{offset: off, size}
         }).collect()
     }
@@ -428,6 +582,235 @@ impl Layout for Wrap {
     }
 }
 
+/// Size of a row or column in a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    /// The track has a fixed size.
+    Fixed(f32),
+    /// The track takes a share of the remaining space proportional to its weight,
+    /// but never less than the minimum size of its children.
+    Fraction(f32),
+    /// The track fits the size of its children.
+    Auto,
+}
+
+/// Placement of a child in a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    /// Horizontal and vertical alignment of the child within the cell.
+    pub offset: (Offset, Offset),
+}
+
+impl Cell {
+    /// A single cell at the given row and column.
+    pub fn new(row: usize, column: usize) -> Self {
+        Cell{row, column, row_span: 1, column_span: 1, offset: (Offset::Start, Offset::Start)}
+    }
+
+    /// Makes the cell span the given number of rows and columns.
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+
+    /// Aligns the child within the cell.
+    pub fn align(mut self, x: Offset, y: Offset) -> Self {
+        self.offset = (x, y);
+        self
+    }
+}
+
+/// Layout of items in rows and columns.
+///
+/// Children are placed in the [`Cell`] given to [`Grid::place`] for their index.
+/// Children without a cell fill the first free cell, row by row.
+/// Rows or columns needed beyond the given tracks are [`Track::Auto`].
+///
+///```rust
+/// # use roost_ui::layouts::{Grid, Track, Cell, Padding};
+/// let mut layout = Grid::new(vec![Track::Auto, Track::Fraction(1.0)], vec![], (16.0, 8.0), Padding::new(8.0));
+/// layout.place(2, Cell::new(1, 0).span(1, 2));
+///```
+#[derive(Debug, Default)]
+pub struct Grid {
+    columns: Vec<Track>,
+    rows: Vec<Track>,
+    cells: BTreeMap<usize, Cell>,
+    gap: (f32, f32),
+    padding: Padding,
+}
+
+/// Cell placements, column sizes and row sizes resolved for a set of children.
+type GridTracks = (Vec<Cell>, Vec<(f32, f32)>, Vec<(f32, f32)>);
+
+impl Grid {
+    /// Creates a grid with the given column and row tracks and `(column, row)` gaps.
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>, gap: (f32, f32), padding: Padding) -> Self {
+        Grid{columns, rows, cells: BTreeMap::new(), gap, padding}
+    }
+
+    /// Places the child at index `child` in `cell`.
+    ///
+    /// Spans of zero are treated as one.
+    pub fn place(&mut self, child: usize, cell: Cell) {
+        self.cells.insert(child, cell.span(cell.row_span, cell.column_span));
+    }
+
+    pub fn padding(&mut self) -> &mut Padding {&mut self.padding}
+
+    fn placements(&self, count: usize) -> Vec<Cell> {
+        let columns = self.columns.len().max(1);
+        let mut taken = HashSet::new();
+        for cell in self.cells.iter().filter(|(i, _)| **i < count).map(|(_, c)| c) {
+            for r in cell.row..cell.row+cell.row_span {
+                for c in cell.column..cell.column+cell.column_span {
+                    taken.insert((r, c));
+                }
+            }
+        }
+
+        let mut next = 0;
+        (0..count).map(|i| match self.cells.get(&i) {
+            Some(cell) => *cell,
+            None => {
+                while taken.contains(&(next / columns, next % columns)) { next += 1; }
+                taken.insert((next / columns, next % columns));
+                Cell::new(next / columns, next % columns)
+            }
+        }).collect()
+    }
+
+    fn track(tracks: &[Track], index: usize) -> Track {
+        tracks.get(index).copied().unwrap_or(Track::Auto)
+    }
+
+    /// Calculates the minimum and maximum size of each track from the `(start, span, min, max)` of the children in it.
+    fn sizes(tracks: &[Track], count: usize, gap: f32, items: Vec<(usize, usize, f32, f32)>) -> Vec<(f32, f32)> {
+        let mut sizes = (0..count).map(|i| match Self::track(tracks, i) {
+            Track::Fixed(s) => (s, s),
+            Track::Fraction(_) => (0.0, f32::MAX),
+            Track::Auto => (0.0, 0.0),
+        }).collect::<Vec<_>>();
+
+        for &(start, _, min, max) in items.iter().filter(|i| i.1 == 1) {
+            match Self::track(tracks, start) {
+                Track::Fixed(_) => {},
+                Track::Fraction(_) => sizes[start].0 = sizes[start].0.max(min),
+                Track::Auto => sizes[start] = (sizes[start].0.max(min), sizes[start].1.max(max)),
+            }
+        }
+
+        for &(start, span, min, _) in items.iter().filter(|i| i.1 > 1) {
+            let current = sizes[start..start+span].iter().map(|s| s.0).sum::<f32>() + gap*(span-1) as f32;
+            let flexible = (start..start+span).filter(|i| !matches!(Self::track(tracks, *i), Track::Fixed(_))).collect::<Vec<_>>();
+            if min > current && !flexible.is_empty() {
+                let extra = (min - current) / flexible.len() as f32;
+                flexible.into_iter().for_each(|i| sizes[i].0 += extra);
+            }
+        }
+
+        sizes.into_iter().map(|(min, max)| (min, max.max(min))).collect()
+    }
+
+    /// Resolves the final size of each track for the available space.
+    fn resolve(tracks: &[Track], sizes: Vec<(f32, f32)>, available: f32, gap: f32) -> Vec<f32> {
+        let gaps = gap * sizes.len().saturating_sub(1) as f32;
+        let mut result = sizes.iter().map(|s| s.0).collect::<Vec<_>>();
+        let weight = |i: usize| match Self::track(tracks, i) {
+            Track::Fraction(w) => Some(w),
+            _ => None,
+        };
+
+        let fractions = (0..sizes.len()).filter(|i| weight(*i).is_some()).collect::<Vec<_>>();
+        if fractions.is_empty() {
+            let autos = (0..sizes.len()).filter(|i| Self::track(tracks, *i) == Track::Auto).collect::<Vec<_>>();
+            if !autos.is_empty() {
+                let free = (available - gaps - result.iter().sum::<f32>()).max(0.0);
+                let auto_min = autos.iter().map(|i| result[*i]).sum::<f32>();
+                let grown = UniformExpand::get(autos.iter().map(|i| sizes[*i]).collect(), auto_min + free, 0.0);
+                autos.into_iter().zip(grown).for_each(|(i, s)| result[i] = s);
+            }
+            return result;
+        }
+
+        let space = available - gaps - (0..sizes.len()).filter(|i| weight(*i).is_none()).map(|i| result[i]).sum::<f32>();
+        let mut flexible = fractions.clone();
+        let mut unit;
+        loop {
+            let total = flexible.iter().map(|i| weight(*i).unwrap()).sum::<f32>();
+            let inflexible = fractions.iter().filter(|i| !flexible.contains(i)).map(|i| result[*i]).sum::<f32>();
+            unit = if total > 0.0 {(space - inflexible).max(0.0) / total} else {0.0};
+            let count = flexible.len();
+            flexible.retain(|i| result[*i] <= unit * weight(*i).unwrap());
+            if flexible.len() == count { break; }
+        }
+        flexible.into_iter().for_each(|i| result[i] = unit * weight(i).unwrap());
+        result
+    }
+
+    fn starts(sizes: &[f32], gap: f32) -> Vec<f32> {
+        let mut offset = 0.0;
+        sizes.iter().map(|s| {
+            let start = offset;
+            offset += s + gap;
+            start
+        }).collect()
+    }
+
+    fn total(sizes: &[(f32, f32)], gap: f32) -> (f32, f32) {
+        let gaps = gap * sizes.len().saturating_sub(1) as f32;
+        let min = sizes.iter().map(|s| s.0).sum::<f32>() + gaps;
+        let max = (sizes.iter().map(|s| s.1).sum::<f32>() + gaps).min(f32::MAX);
+        (min, max.max(min))
+    }
+
+    fn tracks(&self, children: &[SizeRequest]) -> GridTracks {
+        let cells = self.placements(children.len());
+        let columns = cells.iter().map(|c| c.column+c.column_span).fold(self.columns.len(), usize::max);
+        let rows = cells.iter().map(|c| c.row+c.row_span).fold(self.rows.len(), usize::max);
+        let widths = Self::sizes(&self.columns, columns, self.gap.0, cells.iter().zip(children).map(|(c, r)|
+            (c.column, c.column_span, r.min_width(), r.max_width())
+        ).collect());
+        let heights = Self::sizes(&self.rows, rows, self.gap.1, cells.iter().zip(children).map(|(c, r)|
+            (c.row, c.row_span, r.min_height(), r.max_height())
+        ).collect());
+        (cells, widths, heights)
+    }
+}
+
+impl Layout for Grid {
+    fn request_size(&self, _ctx: &mut Context, children: Vec<SizeRequest>) -> SizeRequest {
+        let (_, widths, heights) = self.tracks(&children);
+        let width = Self::total(&widths, self.gap.0);
+        let height = Self::total(&heights, self.gap.1);
+        self.padding.adjust_request(SizeRequest::new(width.0, height.0, width.1, height.1))
+    }
+
+    fn build(&self, _ctx: &mut Context, grid_size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
+        let grid_size = self.padding.adjust_size(grid_size);
+        let (cells, widths, heights) = self.tracks(&children);
+        let widths = Self::resolve(&self.columns, widths, grid_size.0, self.gap.0);
+        let heights = Self::resolve(&self.rows, heights, grid_size.1, self.gap.1);
+        let (xs, ys) = (Self::starts(&widths, self.gap.0), Self::starts(&heights, self.gap.1));
+
+        children.into_iter().zip(cells).map(|(i, cell)| {
+            let last = (cell.column+cell.column_span-1, cell.row+cell.row_span-1);
+            let cell_size = (xs[last.0] + widths[last.0] - xs[cell.column], ys[last.1] + heights[last.1] - ys[cell.row]);
+            let size = i.get(cell_size);
+            let offset = (
+                xs[cell.column] + cell.offset.0.get(cell_size.0, size.0),
+                ys[cell.row] + cell.offset.1.get(cell_size.1, size.1)
+            );
+            Area{offset: self.padding.adjust_offset(offset), size}
+        }).collect()
+    }
+}
+
 /// Defines the reference point for scrolling content.
 #[derive(Debug, Clone, Copy)]
 pub enum ScrollAnchor {
@@ -495,6 +878,11 @@ impl Scroll {
         *self.adjustment.lock().unwrap() = val;
     }
 
+    /// Returns the current scroll value, as clamped by the last build.
+    pub fn scroll(&self) -> f32 {
+        *self.adjustment.lock().unwrap()
+    }
+
     pub fn offset(&mut self) -> &mut Offset { 
         match self.direction {
             ScrollDirection::Vertical => &mut self.offset_y,
@@ -570,6 +958,205 @@ impl Event for AdjustScrollEvent {
     }
 }
 
+type RowBuilder<D> = dyn FnMut(&mut Context, usize, Option<D>) -> D;
+
+/// A vertically scrolling list that only instantiates the rows intersecting its viewport.
+///
+/// Every row is `item_height` tall. The `builder` is called with the index of a row that came into view
+/// and, when available, a recycled row that scrolled out of view, and returns the drawable for that row.
+/// `overscan` extra rows are kept alive above and below the viewport.
+/// The viewport is as tall as the list is given by its parent, the list does not request the height of its rows.
+///
+/// The list scrolls when the mouse is scrolled over it or on an [`AdjustScrollEvent::Vertical`].
+///
+///```rust
+/// # use roost_ui::layouts::VirtualList;
+/// # use roost_ui::drawable::{Shape, ShapeType, Color};
+/// let colors = vec![Color(255, 0, 0, 255); 10_000];
+/// let list = VirtualList::new(colors.len(), 48.0, 8.0, 4, move |_ctx, i, _recycled| {
+///     Shape::new(ShapeType::Rectangle(0.0, (320.0, 48.0), 0.0), colors[i])
+/// });
+///```
+#[derive(Debug)]
+pub struct VirtualList<D: Drawable + 'static>(VirtualView<D>);
+
+impl<D: Drawable + 'static> VirtualList<D> {
+    pub fn new(
+        count: usize, item_height: f32, spacing: f32, overscan: usize,
+        builder: impl FnMut(&mut Context, usize, Option<D>) -> D + 'static
+    ) -> Self {
+        VirtualList(VirtualView(
+            Scroll::vertical(Offset::Start, Offset::Start, Size::Fill, Size::custom(|_| (0.0, f32::MAX)), Padding::default()),
+            VirtualRows{count, item_height, spacing, overscan, rows: Vec::new(), pool: Vec::new(), builder: Box::new(builder)},
+            0.0
+        ))
+    }
+
+    /// Changes the number of items and rebuilds the visible rows on the next frame.
+    pub fn set_count(&mut self, count: usize) {
+        self.0.1.count = count;
+        self.refresh();
+    }
+
+    /// Recycles every visible row so they are rebuilt on the next frame, for when the underlying data changed.
+    pub fn refresh(&mut self) {
+        let rows = self.0.1.rows.drain(..).map(|(_, row)| row).collect::<Vec<_>>();
+        self.0.1.pool.extend(rows);
+    }
+
+    /// Returns the currently instantiated rows with their indices.
+    pub fn rows(&mut self) -> impl Iterator<Item = (usize, &mut D)> {
+        self.0.1.rows.iter_mut().map(|(i, row)| (*i, row))
+    }
+
+    pub fn scroll(&mut self) -> &mut Scroll {&mut self.0.0}
+}
+
+impl<D: Drawable + 'static> _Drawable for VirtualList<D> {
+    fn request_size(&self, ctx: &mut Context) -> RequestBranch {_Drawable::request_size(&self.0, ctx)}
+
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), request: RequestBranch) -> SizedBranch {
+        //The rows are chosen for the new viewport before they are laid out
+        let viewport = request.0.get(size).1;
+        let request = match viewport != self.0.2 {
+            true => {
+                self.0.2 = viewport;
+                self.0.sync(ctx);
+                _Drawable::request_size(&self.0, ctx)
+            },
+            false => request,
+        };
+        _Drawable::build(&mut self.0, ctx, size, request)
+    }
+
+    fn draw(&mut self, sized: SizedBranch, offset: (f32, f32), bound: (f32, f32, f32, f32)) -> Vec<(CanvasArea, CanvasItem)> {
+        _Drawable::draw(&mut self.0, sized, offset, bound)
+    }
+
+    fn event(&mut self, ctx: &mut Context, sized: SizedBranch, event: Box<dyn Event>) {
+        _Drawable::event(&mut self.0, ctx, sized, event)
+    }
+
+    fn layout_node(&self, offset: (f32, f32), sized: &SizedBranch) -> LayoutNode {
+        _Drawable::layout_node(&self.0, offset, sized)
+    }
+}
+
+/// The scroll area of a [`VirtualList`] with the height of its viewport.
+#[derive(Debug)]
+struct VirtualView<D: Drawable + 'static>(Scroll, VirtualRows<D>, f32);
+
+impl<D: Drawable + 'static> VirtualView<D> {
+    fn sync(&mut self, ctx: &mut Context) {
+        let max_scroll = (self.1.height() - self.2).max(0.0);
+        let scroll = self.0.scroll().clamp(0.0, max_scroll);
+        self.1.sync(ctx, scroll, self.2);
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for VirtualView<D> {
+    fn on_event(&mut self, ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{state: MouseState::Scroll(_, y), position: Some(_)}) = event.downcast_ref::<MouseEvent>() {
+            self.0.adjust_scroll(*y);
+            self.sync(ctx);
+        } else if let Some(AdjustScrollEvent::Vertical(y)) = event.downcast_ref::<AdjustScrollEvent>() {
+            self.0.adjust_scroll(*y);
+            self.sync(ctx);
+        } else if event.downcast_ref::<TickEvent>().is_some() {
+            self.sync(ctx);
+        }
+        vec![event]
+    }
+}
+
+impl<D: Drawable + 'static> Component for VirtualView<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
+        &mut self.1 as &mut dyn crate::drawable::Drawable,
+    ]}
+
+    fn children(&self) -> Vec<&dyn Drawable> {vec![
+        &self.1 as &dyn crate::drawable::Drawable,
+    ]}
+
+    fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        crate::layout::Layout::request_size(&self.0, ctx, children)
+    }
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        crate::layout::Layout::build(&self.0, ctx, size, children)
+    }
+}
+
+/// The full height content of a [`VirtualList`], holding only the instantiated rows.
+struct VirtualRows<D: Drawable + 'static> {
+    count: usize,
+    item_height: f32,
+    spacing: f32,
+    overscan: usize,
+    rows: Vec<(usize, D)>,
+    pool: Vec<D>,
+    builder: Box<RowBuilder<D>>,
+}
+
+impl<D: Drawable + 'static> VirtualRows<D> {
+    fn height(&self) -> f32 {
+        (self.count as f32 * (self.item_height + self.spacing) - self.spacing).max(0.0)
+    }
+
+    /// Instantiates the rows visible at `scroll` in a `viewport` tall window and recycles the others.
+    fn sync(&mut self, ctx: &mut Context, scroll: f32, viewport: f32) {
+        let stride = self.item_height + self.spacing;
+        let first = ((scroll / stride).floor().max(0.0) as usize).saturating_sub(self.overscan);
+        let last = ((((scroll + viewport) / stride).ceil().max(0.0) as usize) + self.overscan).min(self.count);
+
+        let (rows, recycled): (Vec<_>, Vec<_>) = self.rows.drain(..).partition(|(i, _)| (first..last).contains(i));
+        self.rows = rows;
+        self.pool.extend(recycled.into_iter().map(|(_, row)| row));
+
+        for i in first..last {
+            if !self.rows.iter().any(|(r, _)| *r == i) {
+                let row = (self.builder)(ctx, i, self.pool.pop());
+                self.rows.push((i, row));
+            }
+        }
+        self.rows.sort_by_key(|(i, _)| *i);
+    }
+}
+
+impl<D: Drawable + 'static> std::fmt::Debug for VirtualRows<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualRows")
+            .field("count", &self.count)
+            .field("item_height", &self.item_height)
+            .field("rows", &self.rows)
+            .finish()
+    }
+}
+
+impl<D: Drawable + 'static> OnEvent for VirtualRows<D> {}
+
+impl<D: Drawable + 'static> Component for VirtualRows<D> {
+    fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {
+        self.rows.iter_mut().map(|(_, row)| row as &mut dyn crate::drawable::Drawable).collect()
+    }
+
+    fn children(&self) -> Vec<&dyn Drawable> {
+        self.rows.iter().map(|(_, row)| row as &dyn crate::drawable::Drawable).collect()
+    }
+
+    fn request_size(&self, _ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
+        let width = children.iter().fold(0.0, |w: f32, c| w.max(c.min_width()));
+        SizeRequest::new(width, self.height(), f32::MAX, self.height())
+    }
+
+    fn build(&mut self, _ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
+        let stride = self.item_height + self.spacing;
+        self.rows.iter().zip(children).map(|((i, _), c)| Area{
+            offset: (0.0, *i as f32 * stride),
+            size: c.get((size.0, self.item_height)),
+        }).collect()
+    }
+}
+
 /// A container pairing a layout with a drawable element.
 #[derive(Debug)]
 pub struct Bin<L: Layout + 'static, D: Drawable + 'static>(pub L, pub D);
@@ -603,49 +1190,181 @@ impl<L: Layout + 'static, D: Drawable + 'static> Bin<L, D> {
 }
 
 /// A container that optionally displays a drawable item, toggling between visible and hidden states.
+///
+/// With a [`Transition`] the item animates in and out, staying drawn until it has animated out.
 #[derive(Debug)]
-pub struct Opt<D: Drawable + 'static>(Stack, Option<D>, Option<D>);
-impl<D: Drawable + 'static> OnEvent for Opt<D> {}
+pub struct Opt<D: Drawable + 'static> {
+    layout: Stack,
+    shown: Transitioned<D>,
+    hidden: Option<D>,
+    display: bool,
+    transition: Option<Transition>,
+    progress: Animation<f32>,
+}
+
+impl<D: Drawable + 'static> OnEvent for Opt<D> {
+    fn on_event(&mut self, _ctx: &mut Context, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if self.progress.tick(&*event) {
+            self.shown.progress = self.progress.value();
+            if !self.display && !self.progress.is_running() && self.hidden.is_none() {
+                self.hidden = self.shown.item.take();
+            }
+        }
+        vec![event]
+    }
+}
 
 impl<D: Drawable + 'static> Component for Opt<D> {
     fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {vec![
-        &mut self.1 as &mut dyn crate::drawable::Drawable,
+        &mut self.shown as &mut dyn crate::drawable::Drawable,
     ]}
 
     fn children(&self) -> Vec<&dyn Drawable> {vec![
-        &self.1 as &dyn crate::drawable::Drawable,
+        &self.shown as &dyn crate::drawable::Drawable,
     ]}
 
     fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
-        crate::layout::Layout::request_size(&self.0, ctx, children)
+        crate::layout::Layout::request_size(&self.layout, ctx, children)
     }
     fn build(&mut self, ctx: &mut Context, size: (f32, f32), children: Vec<crate::layout::SizeRequest>) -> Vec<crate::layout::Area> {
-        crate::layout::Layout::build(&self.0, ctx, size, children)
+        crate::layout::Layout::build(&self.layout, ctx, size, children)
     }
 }
 
 impl<D: Drawable + 'static> Opt<D> {
     pub fn new(item: D, display: bool) -> Self {
-        match display {
-            true => Opt(Stack::default(), Some(item), None),
-            false => Opt(Stack::default(), None, Some(item)),
+        let (shown, hidden) = match display {
+            true => (Some(item), None),
+            false => (None, Some(item)),
+        };
+        let progress = if display {1.0} else {0.0};
+        Opt{
+            layout: Stack::default(),
+            shown: Transitioned{item: shown, transition: None, progress, display},
+            hidden,
+            display,
+            transition: None,
+            progress: Animation::new(progress),
         }
     }
 
+    /// Sets how the item animates in and out, `None` shows and hides it instantly.
+    pub fn set_transition(&mut self, transition: Option<Transition>) {
+        self.transition = transition;
+        self.shown.transition = transition;
+    }
+
     pub fn display(&mut self, display: bool) {
-        match display {
-            true if self.1.is_none() => self.1 = self.2.take(),
-            false if self.2.is_none() => self.2 = self.1.take(),
-            _ => {}
+        if display == self.display { return; }
+        self.display = display;
+        self.shown.display = display;
+        if display && self.shown.item.is_none() {
+            self.shown.item = self.hidden.take();
+        }
+        let target = if display {1.0} else {0.0};
+        match self.transition {
+            Some(transition) if self.shown.item.is_some() => {
+                //Reversing halfway through only takes the remaining part of the duration
+                let duration = transition.duration.mul_f32((target - self.progress.value()).abs());
+                self.progress.tween(target, duration, transition.easing);
+            },
+            _ => {
+                self.progress.set(target);
+                self.shown.progress = target;
+                if !display {
+                    self.hidden = self.shown.item.take();
+                }
+            },
         }
     }
 
     pub fn inner(&mut self) -> &mut D {
-        self.1.as_mut().unwrap_or_else(|| self.2.as_mut().unwrap())
+        self.shown.item.as_mut().unwrap_or_else(|| self.hidden.as_mut().unwrap())
     }
 
     pub fn is_showing(&self) -> bool {
-        self.1.is_some()
+        self.display
+    }
+
+    /// Returns `true` while the item is animating in or out.
+    pub fn is_transitioning(&self) -> bool {
+        self.progress.is_running()
+    }
+}
+
+/// The shown or outgoing item of an [`Opt`], drawn with the effects of its transition.
+#[derive(Debug)]
+struct Transitioned<D: Drawable + 'static> {
+    item: Option<D>,
+    transition: Option<Transition>,
+    /// 0.0 when the item is hidden and 1.0 when it is shown.
+    progress: f32,
+    /// `false` once the item is hidden, while it animates out it no longer takes pointer input.
+    display: bool,
+}
+
+impl<D: Drawable + 'static> Transitioned<D> {
+    fn effect(&self, offset: (f32, f32), size: (f32, f32)) -> Effect {
+        let Some(transition) = self.transition else { return Effect::default() };
+        let hidden = 1.0 - self.progress;
+        let translate = match transition.slide {
+            Some(Edge::Top) => (0.0, -size.1 * hidden),
+            Some(Edge::Bottom) => (0.0, size.1 * hidden),
+            Some(Edge::Left) => (-size.0 * hidden, 0.0),
+            Some(Edge::Right) => (size.0 * hidden, 0.0),
+            None => (0.0, 0.0),
+        };
+        Effect{
+            opacity: if transition.fade {self.progress} else {1.0},
+            translate,
+            scale: transition.scale.map_or(1.0, |from| from + (1.0 - from) * self.progress),
+            origin: (offset.0 + size.0 / 2.0, offset.1 + size.1 / 2.0),
+            ..Effect::default()
+        }
+    }
+
+    /// Maps a position relative to the transformed hit area to one relative to the item's hit area.
+    fn to_item(&self, size: (f32, f32), position: (f32, f32)) -> (f32, f32) {
+        let (hit, _) = self.hit_area(size);
+        let (inner, _) = _Drawable::hit_area(&self.item, size);
+        let p = self.effect((0.0, 0.0), size).invert((position.0 + hit.0, position.1 + hit.1));
+        (p.0 - inner.0, p.1 - inner.1)
+    }
+}
+
+impl<D: Drawable + 'static> _Drawable for Transitioned<D> {
+    fn request_size(&self, ctx: &mut Context) -> RequestBranch {_Drawable::request_size(&self.item, ctx)}
+
+    fn build(&mut self, ctx: &mut Context, size: (f32, f32), request: RequestBranch) -> SizedBranch {
+        _Drawable::build(&mut self.item, ctx, size, request)
+    }
+
+    fn draw(&mut self, sized: SizedBranch, offset: (f32, f32), bound: (f32, f32, f32, f32)) -> Vec<(CanvasArea, CanvasItem)> {
+        let effect = self.effect(offset, sized.0);
+        effect.apply(_Drawable::draw(&mut self.item, sized, offset, bound))
+    }
+
+    fn name(&self) -> String {_Drawable::name(&self.item)}
+
+    fn event(&mut self, ctx: &mut Context, sized: SizedBranch, event: Box<dyn Event>) {
+        if !self.display && is_pointer(&*event) { return; }
+        let size = sized.0;
+        let event = map_position(event, |p| self.to_item(size, p));
+        _Drawable::event(&mut self.item, ctx, sized, event)
+    }
+
+    fn layout_node(&self, offset: (f32, f32), sized: &SizedBranch) -> LayoutNode {
+        _Drawable::layout_node(&self.item, offset, sized)
+    }
+
+    fn hit_area(&self, size: (f32, f32)) -> ((f32, f32), (f32, f32)) {
+        if !self.display { return ((0.0, 0.0), (0.0, 0.0)); }
+        let (offset, area) = _Drawable::hit_area(&self.item, size);
+        self.effect((0.0, 0.0), size).bounds(offset, area)
+    }
+
+    fn contains(&self, size: (f32, f32), position: (f32, f32)) -> bool {
+        self.display && _Drawable::contains(&self.item, size, self.to_item(size, position))
     }
 }
 
@@ -684,22 +1403,35 @@ impl<L: Drawable + 'static, R: Drawable + 'static> EitherOr<L, R> {
         self.2.display(!display_left);
     }
 
+    /// Sets how the two items animate when switching between them.
+    pub fn set_transition(&mut self, transition: Option<Transition>) {
+        self.1.set_transition(transition);
+        self.2.set_transition(transition);
+    }
+
     pub fn left(&mut self) -> &mut L { self.1.inner() }
     pub fn right(&mut self) -> &mut R { self.2.inner() }
 }
 
 /// A container that holds multiple drawables but displays only one at a time, allowing toggling between them.
+///
+/// The displayed item is drawn over the others, so it covers the outgoing item while they transition.
 #[derive(Debug)]
-pub struct Enum(Stack, HashMap<String, Opt<Box<dyn Drawable>>>);
+pub struct Enum(Stack, Vec<(String, Opt<Box<dyn Drawable>>)>, usize);
 impl OnEvent for Enum {}
 
 impl Component for Enum {
+    //The displayed item comes last so it is drawn over the others
     fn children_mut(&mut self) -> Vec<&mut dyn Drawable> {
-        self.1.values_mut().map(|v| v as &mut dyn crate::drawable::Drawable).collect()
+        let (before, rest) = self.1.split_at_mut(self.2);
+        let Some((shown, after)) = rest.split_first_mut() else { return vec![] };
+        before.iter_mut().chain(after).chain([shown]).map(|(_, v)| v as &mut dyn crate::drawable::Drawable).collect()
     }
 
     fn children(&self) -> Vec<&dyn Drawable> {
-        self.1.values().map(|v| v as &dyn crate::drawable::Drawable).collect()
+        let (before, rest) = self.1.split_at(self.2);
+        let Some((shown, after)) = rest.split_first() else { return vec![] };
+        before.iter().chain(after).chain([shown]).map(|(_, v)| v as &dyn crate::drawable::Drawable).collect()
     }
 
     fn request_size(&self, ctx: &mut Context, children: Vec<crate::layout::SizeRequest>) -> crate::layout::SizeRequest {
@@ -714,24 +1446,186 @@ impl Enum {
     /// Creates a new [`Enum`] component with the given drawable items.
     /// The first item will be visible by default.
     pub fn new(items: Vec<(&str, Box<dyn Drawable>)>, start: &str) -> Self {
+        let shown = items.iter().position(|(name, _)| *name == start).unwrap_or_default();
         let items = items.into_iter().map(|(name, item)| {
             (name.to_string(), Opt::new(item, name == start))
         }).collect::<Vec<(String, Opt<Box<dyn Drawable>>)>>();
 
-        Enum(Stack::default(), items.into_iter().collect())
+        Enum(Stack::default(), items, shown)
     }
 
     /// Displays only the item matching the given name and hides all others. 
     /// If the key doesn't exist, defaults to the first item.
     pub fn display(&mut self, name: &str) {
-        let key = match self.1.contains_key(name) { 
-            true => name.to_string(),
-            false => self.1.keys().next().unwrap().clone()
-        };
-
-        for (k, v) in self.1.iter_mut() {
-            v.display(*k == key);
+        self.2 = self.1.iter().position(|(k, _)| k == name).unwrap_or_default();
+        for (i, (_, v)) in self.1.iter_mut().enumerate() {
+            v.display(i == self.2);
         }
     }
 
+    /// Sets how the items animate when switching between them.
+    pub fn set_transition(&mut self, transition: Option<Transition>) {
+        self.1.iter_mut().for_each(|(_, v)| v.set_transition(transition));
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flex_grows_by_weight_from_the_basis() {
+        let flex = vec![Flex::new(1.0, 1.0, Some(20.0)), Flex::new(3.0, 1.0, Some(20.0))];
+        assert_eq!(Flex::get(vec![(0.0, 200.0), (0.0, 200.0)], flex, 120.0, 0.0), vec![40.0, 80.0]);
+    }
+
+    #[test]
+    fn flex_growth_stops_at_the_maximum() {
+        let flex = vec![Flex::default(), Flex::default()];
+        assert_eq!(Flex::get(vec![(0.0, 10.0), (0.0, 200.0)], flex, 100.0, 10.0), vec![10.0, 80.0]);
+    }
+
+    #[test]
+    fn flex_shrinks_by_weight_times_basis() {
+        let flex = vec![Flex::new(1.0, 1.0, Some(100.0)), Flex::new(1.0, 1.0, Some(50.0))];
+        assert_eq!(Flex::get(vec![(0.0, 200.0), (0.0, 200.0)], flex, 120.0, 0.0), vec![80.0, 40.0]);
+    }
+
+    #[test]
+    fn flex_limits_hold_rigid_items_at_their_basis() {
+        assert_eq!(Flex::new(0.0, 0.0, Some(40.0)).limits((10.0, 100.0)), (40.0, 40.0));
+        assert_eq!(Flex::new(1.0, 0.0, Some(40.0)).limits((10.0, 100.0)), (40.0, 100.0));
+        assert_eq!(Flex::new(0.0, 1.0, Some(400.0)).limits((10.0, 100.0)), (10.0, 100.0));
+        assert_eq!(Flex::default().limits((10.0, 100.0)), (10.0, 100.0));
+    }
+
+    #[test]
+    fn justify_distributes_the_leftover_space() {
+        assert_eq!(Justify::Start.get(90.0, 3), (0.0, 0.0));
+        assert_eq!(Justify::Center.get(90.0, 3), (45.0, 0.0));
+        assert_eq!(Justify::End.get(90.0, 3), (90.0, 0.0));
+        assert_eq!(Justify::SpaceBetween.get(90.0, 3), (0.0, 45.0));
+        assert_eq!(Justify::SpaceAround.get(90.0, 3), (15.0, 30.0));
+        assert_eq!(Justify::SpaceEvenly.get(90.0, 2), (30.0, 30.0));
+    }
+
+    #[test]
+    fn justify_ignores_overflow_and_single_items() {
+        assert_eq!(Justify::Center.get(-20.0, 3), (0.0, 0.0));
+        assert_eq!(Justify::SpaceBetween.get(50.0, 1), (0.0, 0.0));
+        assert_eq!(Justify::SpaceAround.get(50.0, 0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn grid_fraction_tracks_share_the_remaining_space() {
+        let tracks = [Track::Fixed(20.0), Track::Fraction(1.0), Track::Fraction(3.0)];
+        let sizes = Grid::sizes(&tracks, 3, 10.0, vec![]);
+        assert_eq!(Grid::resolve(&tracks, sizes, 120.0, 10.0), vec![20.0, 20.0, 60.0]);
+    }
+
+    #[test]
+    fn grid_fraction_tracks_keep_their_minimum() {
+        let tracks = [Track::Fraction(1.0), Track::Fraction(1.0)];
+        let sizes = Grid::sizes(&tracks, 2, 0.0, vec![(0, 1, 80.0, 80.0)]);
+        assert_eq!(Grid::resolve(&tracks, sizes, 100.0, 0.0), vec![80.0, 20.0]);
+    }
+
+    #[test]
+    fn grid_spanning_children_grow_flexible_tracks() {
+        let tracks = [Track::Fixed(10.0), Track::Auto, Track::Auto];
+        let sizes = Grid::sizes(&tracks, 3, 5.0, vec![(0, 3, 60.0, 60.0)]);
+        assert_eq!(sizes.iter().map(|s| s.0).collect::<Vec<_>>(), vec![10.0, 20.0, 20.0]);
+    }
+
+    #[test]
+    fn grid_auto_placement_skips_taken_cells() {
+        let mut grid = Grid::new(vec![Track::Auto, Track::Auto], vec![], (0.0, 0.0), Padding::default());
+        grid.place(0, Cell::new(0, 0).span(1, 2));
+        let cells = grid.placements(3);
+        assert_eq!((cells[1].row, cells[1].column), (1, 0));
+        assert_eq!((cells[2].row, cells[2].column), (1, 1));
+    }
+
+    #[test]
+    fn grid_zero_spans_count_as_one() {
+        let mut grid = Grid::new(vec![Track::Auto], vec![], (0.0, 0.0), Padding::default());
+        grid.place(0, Cell{row_span: 0, column_span: 0, ..Cell::new(0, 0)});
+        let (cells, widths, heights) = grid.tracks(&[SizeRequest::fixed((10.0, 10.0))]);
+        assert_eq!((cells[0].row_span, cells[0].column_span), (1, 1));
+        assert_eq!((widths.len(), heights.len()), (1, 1));
+    }
+
+    #[test]
+    fn transitioned_items_are_hit_where_they_are_drawn() {
+        let mut ctx = Context::headless(None);
+        let probe = crate::headless::Probe::new(crate::drawable::Shape::new(
+            crate::drawable::ShapeType::Rectangle(0.0, (100.0, 100.0), 0.0), crate::drawable::Color(0, 0, 0, 255)
+        ));
+        let log = probe.log();
+        let transition = Transition::scale(0.5, std::time::Duration::from_secs(1));
+        let mut item = Transitioned{item: Some(probe), transition: Some(transition), progress: 0.0, display: true};
+        assert_eq!(item.hit_area((100.0, 100.0)), ((25.0, 25.0), (50.0, 50.0)));
+
+        let press = |position| Box::new(MouseEvent{position: Some(position), state: MouseState::Pressed});
+        let request = _Drawable::request_size(&item, &mut ctx);
+        let sized = _Drawable::build(&mut item, &mut ctx, (100.0, 100.0), request);
+        _Drawable::event(&mut item, &mut ctx, sized.clone(), press((25.0, 25.0)));
+        assert!(log.contains("(50.0, 50.0)"));
+
+        log.clear();
+        item.display = false;
+        assert_eq!(item.hit_area((100.0, 100.0)).1, (0.0, 0.0));
+        _Drawable::event(&mut item, &mut ctx, sized, press((25.0, 25.0)));
+        assert!(log.events().is_empty());
+    }
+
+    #[test]
+    fn virtual_lists_build_visible_rows_and_recycle_them() {
+        use crate::events::{RawInput, TouchPhase, MouseScrollDelta};
+        let builds = Arc::new(Mutex::new(Vec::new()));
+        let log = builds.clone();
+        let list = VirtualList::new(1000, 10.0, 0.0, 2, move |_ctx, i, recycled: Option<crate::drawable::Shape>| {
+            log.lock().unwrap().push((i, recycled.is_some()));
+            crate::drawable::Shape::new(crate::drawable::ShapeType::Rectangle(0.0, (100.0, 10.0), 0.0), crate::drawable::Color(0, 0, 0, 255))
+        });
+        let mut app = crate::headless::Headless::with_root(Context::headless(None), list, (100, 100), 1.0);
+        app.frame();
+        let built = builds.lock().unwrap().drain(..).collect::<Vec<_>>();
+        assert_eq!(built, (0..12).map(|i| (i, false)).collect::<Vec<_>>());
+        assert_eq!(app.layout().children[0].children.len(), 12);
+
+        //Scrolling by 200 shows rows 20 to 30, with two rows of overscan on each side
+        app.move_to((50.0, 50.0));
+        app.input(RawInput::MouseWheel{delta: MouseScrollDelta::PixelDelta((0.0, 0.0).into()), phase: TouchPhase::Started});
+        app.input(RawInput::MouseWheel{delta: MouseScrollDelta::PixelDelta((0.0, -1000.0).into()), phase: TouchPhase::Moved});
+        app.frame();
+        let built = builds.lock().unwrap().drain(..).collect::<Vec<_>>();
+        assert_eq!(built.iter().map(|(i, _)| *i).collect::<Vec<_>>(), (18..32).collect::<Vec<_>>());
+        assert_eq!(built.iter().filter(|(_, recycled)| *recycled).count(), 12);
+        assert_eq!(app.layout().children[0].children.len(), 14);
+
+        app.frame();
+        assert!(builds.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn empty_rows_and_columns_only_request_their_padding() {
+        let mut ctx = Context::headless(None);
+        let row = Row::new(10.0, Offset::Start, Size::Fit, Padding::new(4.0));
+        assert_eq!(row.request_size(&mut ctx, vec![]), SizeRequest::fixed((8.0, 8.0)));
+        assert!(row.build(&mut ctx, (100.0, 100.0), vec![]).is_empty());
+        let column = Column::new(10.0, Offset::Start, Size::Fit, Padding::default());
+        assert_eq!(column.request_size(&mut ctx, vec![]), SizeRequest::default());
+        assert!(column.build(&mut ctx, (100.0, 100.0), vec![]).is_empty());
+    }
+
+    #[test]
+    fn padding_larger_than_the_size_leaves_no_room() {
+        let padding = Padding(30.0, 10.0, 30.0, 10.0);
+        assert_eq!(padding.adjust_size((40.0, 100.0)), (0.0, 80.0));
+        let mut ctx = Context::headless(None);
+        let areas = Row::new(0.0, Offset::Start, Size::Fit, padding).build(&mut ctx, (40.0, 100.0), vec![SizeRequest::fill()]);
+        assert_eq!(areas[0], Area{offset: (30.0, 10.0), size: (0.0, 80.0)});
+    }
 }
@@ -6,11 +6,12 @@
 
 extern crate self as roost_ui;
 
-use std::collections::BTreeMap;
-use std::any::TypeId;
+use std::collections::{BTreeMap, HashSet};
+use std::any::{Any, TypeId};
+use std::time::{Duration, Instant};
 
-use wgpu_canvas::{Atlas, Item as CanvasItem};
-use maverick_os::window::Lifetime;
+use wgpu_canvas::{Atlas, Area as CanvasArea, Item as CanvasItem};
+use maverick_os::window::{Input, Lifetime};
 pub use maverick_os::{ 
     State, 
     window::Event as WindowEvent,
@@ -33,11 +34,37 @@ mod wgpu;
 use wgpu::Canvas;
 
 pub mod events;
-use events::{EventHandler, Events, Event, TickEvent};
+use events::{EventHandler, Events, Event, TickEvent, RawInput, FocusEvent, KeyboardEvent, KeyboardState, MouseEvent, MouseState, Key, NamedKey, Pointer, DragEvent, DragState, Payload, PointerEnter, PointerLeave, CursorIcon, CommandEvent, Capture, PointerEvent, PointerKind, PointerState};
+
+mod focus;
+use focus::Focus;
+
+/// # roost shortcuts
+///
+/// Key chords bound to named commands with [`Context::register_shortcut`].
+pub mod shortcuts;
+use shortcuts::{Shortcut, ShortcutConflict, Shortcuts};
+
+/// # roost clipboard
+///
+/// Text and image copy and paste through [`Context::clipboard`].
+pub mod clipboard;
+use clipboard::{Clipboard, MemoryClipboard, SystemClipboard};
+
+/// # roost animation
+///
+/// Tweens and springs over `f32`, `(f32, f32)` and [`Color`](drawable::Color), driven by [`TickEvent`]s,
+/// and the [`Transition`](animation::Transition)s of the containers in [`layouts`].
+pub mod animation;
+
+/// # roost editor
+///
+/// The text editing model behind [`TextInput`](emitters::TextInput).
+pub mod editor;
 
 pub mod layouts;
 pub mod layout;
-use layout::Scale;
+use layout::{Area, Scale, Scaling, SizeRequest};
 
 /// # roost emitters
 ///
@@ -46,10 +73,24 @@ use layout::Scale;
 /// Each emitter listens for raw input events and translates them
 /// into meaningful, component-specific events.
 pub mod emitters;
+use emitters::{Drag, DragPreview};
+
+/// # roost recording
+///
+/// Captures input sessions with [`Context::start_recording`] so they can be saved
+/// and replayed deterministically with [`Context::replay`] or [`headless::Headless::replay`].
+pub mod recording;
+use recording::{Recorder, Recording, Replayer, Step};
 
 pub mod drawable;
 pub use drawable::Component;
-use drawable::{Drawable, _Drawable, SizedBranch};
+use drawable::{Drawable, _Drawable, SizedBranch, LayoutNode};
+
+/// # roost headless
+///
+/// Runs an application or a single drawable without a window or a physical GPU,
+/// rendering frames into an offscreen texture that can be read back as an image.
+pub mod headless;
 
 //pub mod components;
 // pub mod config;
@@ -60,6 +101,9 @@ pub mod resources {
 
 type PluginList = BTreeMap<TypeId, Box<dyn Plugin>>;
 
+/// The longest [`TickEvent::delta`](events::TickEvent), so animations do not jump after the app was paused.
+pub const MAX_FRAME_DELTA: Duration = Duration::from_millis(100);
+
 pub trait Plugin: Downcast {
     fn event(&mut self, _ctx: &mut Context, _event: &dyn Event) {}
 }
@@ -154,32 +198,118 @@ impl<'a, P: Plugin> Drop for PluginGuard<'a, P> {
 
 /// `Context` holds the app context, including hardware, runtime, assets, theme, plugins, events, and state.
 pub struct Context {
-    pub hardware: HardwareContext,
-    pub runtime: RuntimeContext,
+    hardware: Option<HardwareContext>,
+    runtime: Option<RuntimeContext>,
     pub assets: Assets,
     plugins: PluginList,
     events: Events,
-    state: Option<State>
+    state: Option<State>,
+    recorder: Option<Recorder>,
+    replayer: Option<Replayer>,
+    strict_layout: bool,
+    focus: Focus,
+    bubbling: Vec<Box<dyn Event>>,
+    pointer_capture: Option<Capture>,
+    pressed_pointer: Option<(PointerKind, u64)>,
+    cursor: (f32, f32),
+    drag: Option<Drag>,
+    hovered: HashSet<Vec<usize>>,
+    built_hovered: HashSet<Vec<usize>>,
+    cursor_icon: Option<CursorIcon>,
+    shortcuts: Shortcuts,
+    clipboard: Box<dyn Clipboard>,
+    ime_area: Option<((f32, f32), (f32, f32))>,
+    time: Duration,
 }
 
 impl Context {
     /// Creates a new `Context` instance and loads the default Pelican UI assets.
     pub fn new(hardware: HardwareContext, runtime: RuntimeContext, state: Option<State>) -> Self {
         Context {
-            hardware,
-            runtime,
+            hardware: Some(hardware),
+            runtime: Some(runtime),
             assets: Assets::new(),  
             plugins: PluginList::new(),
             events: Events::new(),    
-            state
+            state,
+            recorder: None,
+            replayer: None,
+            strict_layout: false,
+            focus: Focus::default(),
+            bubbling: Vec::new(),
+            pointer_capture: None,
+            pressed_pointer: None,
+            cursor: (0.0, 0.0),
+            drag: None,
+            hovered: HashSet::new(),
+            built_hovered: HashSet::new(),
+            cursor_icon: None,
+            shortcuts: Shortcuts::default(),
+            clipboard: Box::new(SystemClipboard::new()),
+            ime_area: None,
+            time: Duration::ZERO,
         }
     }
 
+    /// Creates a `Context` that is not backed by an OS window or device.
+    ///
+    /// Used by [`headless`] runs, where [`Context::hardware`] and [`Context::runtime`] are unavailable.
+    pub fn headless(state: Option<State>) -> Self {
+        Context {
+            hardware: None,
+            runtime: None,
+            assets: Assets::new(),
+            plugins: PluginList::new(),
+            events: Events::new(),
+            state,
+            recorder: None,
+            replayer: None,
+            strict_layout: false,
+            focus: Focus::default(),
+            bubbling: Vec::new(),
+            pointer_capture: None,
+            pressed_pointer: None,
+            cursor: (0.0, 0.0),
+            drag: None,
+            hovered: HashSet::new(),
+            built_hovered: HashSet::new(),
+            cursor_icon: None,
+            shortcuts: Shortcuts::default(),
+            clipboard: Box::new(MemoryClipboard::default()),
+            ime_area: None,
+            time: Duration::ZERO,
+        }
+    }
+
+    /// Returns `true` if this context is not backed by an OS window or device.
+    pub fn is_headless(&self) -> bool {
+        self.hardware.is_none()
+    }
+
+    /// Returns a mutable reference to the [`HardwareContext`], or `None` in a headless `Context`.
+    pub fn hardware(&mut self) -> Option<&mut HardwareContext> {
+        self.hardware.as_mut()
+    }
+
+    /// Returns a mutable reference to the [`RuntimeContext`], or `None` in a headless `Context`.
+    pub fn runtime(&mut self) -> Option<&mut RuntimeContext> {
+        self.runtime.as_mut()
+    }
+
     /// Adds an [`Event`] to the context's event queue to be triggered.
     pub fn trigger_event(&mut self, event: impl Event + 'static) {
         self.events.push_back(Box::new(event));
     }
 
+    /// Emits an [`Event`] that bubbles up from the component handling the current event
+    /// through each of its ancestors' [`OnEvent::on_bubble`], until one of them stops it.
+    ///
+    /// Unlike [`Context::trigger_event`], the event is not dispatched from the root
+    /// and is dropped once it reaches the root.
+    pub fn bubble(&mut self, event: impl Event + 'static) {
+        self.bubbling.push(Box::new(event));
+    }
+
     pub fn get<P: Plugin + 'static>(&mut self) -> PluginGuard<'_, P> {
         PluginGuard(Some(*self.plugins.remove(&TypeId::of::<P>())
             .unwrap_or_else(|| panic!("Plugin Not Configured: {:?}", std::any::type_name::<P>()))
@@ -191,6 +321,312 @@ impl Context {
         self.state.as_mut().unwrap()
     }
 
+    /// Sets how invalid layouts, such as a [`SizeRequest`](layout::SizeRequest) with min > max, are handled.
+    ///
+    /// When strict, the app panics with the name of the offending component.
+    /// Otherwise the invalid sizes are clamped and a warning is logged.
+    /// Layouts are lenient by default, turn strict layouts on in tests to catch invalid sizes where they are returned.
+    pub fn set_strict_layout(&mut self, strict: bool) {
+        self.strict_layout = strict;
+    }
+
+    /// Returns the request when valid, otherwise panics or sanitizes it depending on [`Context::set_strict_layout`].
+    pub(crate) fn check_request(&self, name: impl FnOnce() -> String, request: SizeRequest) -> SizeRequest {
+        match request.validate() {
+            Ok(()) => request,
+            Err(error) if self.strict_layout => panic!("Invalid layout in {}: {error}", name()),
+            Err(error) => {
+                log::warn!("Invalid layout in {}: {error}", name());
+                request.sanitize()
+            }
+        }
+    }
+
+    /// Returns the area when valid, otherwise panics or sanitizes it depending on [`Context::set_strict_layout`].
+    pub(crate) fn check_area(&self, name: impl FnOnce() -> String, area: Area) -> Area {
+        match area.validate() {
+            Ok(()) => area,
+            Err(error) if self.strict_layout => panic!("Invalid layout in {}: {error}", name()),
+            Err(error) => {
+                log::warn!("Invalid layout in {}: {error}", name());
+                area.sanitize()
+            }
+        }
+    }
+
+    /// Starts recording every input and frame, discarding any unfinished recording.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Recorder::new());
+    }
+
+    /// Stops recording and returns the [`Recording`], if one was started.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    /// Returns `true` while a recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Replays a [`Recording`], feeding the inputs recorded before each frame into the next drawn frame.
+    ///
+    /// Window input is ignored until the replay has finished, so the replay is deterministic.
+    pub fn replay(&mut self, recording: Recording) {
+        self.replayer = Some(Replayer::new(recording));
+    }
+
+    /// Returns `true` while a [`Recording`] is being replayed.
+    pub fn is_replaying(&self) -> bool {
+        self.replayer.is_some()
+    }
+
+    /// Moves the keyboard focus to the focusable node with the given `id`.
+    ///
+    /// A [`FocusEvent::Lost`] is triggered for the previously focused node
+    /// and a [`FocusEvent::Gained`] for the new one.
+    /// While a node is focused, [`KeyboardEvent`]s are only passed down to it and its descendants.
+    pub fn focus(&mut self, id: uuid::Uuid) {
+        self.set_focus(Some(id));
+    }
+
+    /// Clears the keyboard focus, so [`KeyboardEvent`]s are passed to every component again.
+    pub fn blur(&mut self) {
+        self.set_focus(None);
+    }
+
+    /// Returns the id of the focused node, if any.
+    pub fn focused(&self) -> Option<uuid::Uuid> {
+        self.focus.focused
+    }
+
+    /// Returns `true` if the node with the given `id` has the keyboard focus.
+    pub fn is_focused(&self, id: uuid::Uuid) -> bool {
+        self.focus.focused == Some(id)
+    }
+
+    /// Registers the node being built as focusable.
+    ///
+    /// Must be called from [`Component::build`] on every layout pass, which keeps the
+    /// Tab order in sync with the tree. Nodes with a `tab_index` are visited first,
+    /// in ascending order, followed by the other nodes in tree order.
+    pub fn register_focusable(&mut self, id: uuid::Uuid, tab_index: Option<u32>) {
+        self.focus.register(id, tab_index);
+    }
+
+    /// Moves the focus to the next focusable node in Tab order, or to the previous one when `reverse` is set.
+    pub fn focus_next(&mut self, reverse: bool) {
+        if let Some(id) = self.focus.next(reverse) {
+            self.focus(id);
+        }
+    }
+
+    fn set_focus(&mut self, id: Option<uuid::Uuid>) {
+        let previous = std::mem::replace(&mut self.focus.focused, id);
+        if previous != id {
+            if let Some(previous) = previous {
+                self.trigger_event(FocusEvent::Lost(previous));
+            }
+            if let Some(id) = id {
+                self.trigger_event(FocusEvent::Gained(id));
+            }
+        }
+    }
+
+    /// Captures the pointer for the component handling the current event, usually a
+    /// [`MouseEvent`] with [`MouseState::Pressed`].
+    ///
+    /// Until the pointer is released, `Moved`, `Scroll` and `Released` mouse events, and the
+    /// [`PointerEvent`](events::PointerEvent)s of the last pressed pointer, are passed only to the
+    /// capturing component, with a position relative to its origin even when the pointer is outside of its bounds.
+    /// The capture ends once both are released, or with [`Context::release_pointer`].
+    pub fn capture_pointer(&mut self) {
+        self.pointer_capture = Some(Capture{path: self.focus.path().to_vec(), mouse: true, pointer: self.pressed_pointer});
+    }
+
+    /// Ends a pointer capture started with [`Context::capture_pointer`].
+    pub fn release_pointer(&mut self) {
+        self.pointer_capture = None;
+    }
+
+    /// Returns `true` while a component has captured the pointer.
+    pub fn is_pointer_captured(&self) -> bool {
+        self.pointer_capture.is_some()
+    }
+
+    /// Starts dragging `payload`, replacing any drag in progress.
+    ///
+    /// Until the pointer is released, a [`DragEvent`] is triggered whenever the pointer moves,
+    /// and the `preview` is drawn above the app, following the pointer.
+    /// Usually called by a [`Draggable`](emitters::Draggable).
+    pub fn start_drag(&mut self, payload: Box<dyn Any>, preview: Option<DragPreview>) {
+        self.drag = Some(Drag{payload: Payload(payload.into()), preview, position: self.cursor});
+    }
+
+    /// Cancels the drag in progress, triggering a [`DragEvent`] with [`DragState::Cancelled`].
+    pub fn cancel_drag(&mut self) {
+        if let Some(drag) = self.drag.take() {
+            self.trigger_event(DragEvent{position: None, state: DragState::Cancelled, payload: drag.payload});
+        }
+    }
+
+    /// Returns the payload of the drag in progress, if any.
+    pub fn drag_payload(&self) -> Option<&Payload> {
+        self.drag.as_ref().map(|drag| &drag.payload)
+    }
+
+    /// Returns `true` while a drag is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Sets the cursor icon shown over the window.
+    ///
+    /// Call it while handling a [`MouseEvent`] with a position, the cursor is reset to
+    /// [`CursorIcon::Default`] before every mouse event, so the last component under the
+    /// pointer to set an icon, usually the deepest one, decides the cursor.
+    pub fn set_cursor(&mut self, icon: CursorIcon) {
+        self.cursor_icon = Some(icon);
+    }
+
+    /// Returns the clipboard, the [`SystemClipboard`] or a [`MemoryClipboard`] in headless contexts.
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        &mut *self.clipboard
+    }
+
+    /// Replaces the clipboard, for example with a [`MemoryClipboard`] in tests.
+    pub fn set_clipboard(&mut self, clipboard: impl Clipboard + 'static) {
+        self.clipboard = Box::new(clipboard);
+    }
+
+    /// Returns the sum of the [`TickEvent`] deltas so far.
+    ///
+    /// Unlike the wall clock, this follows the recorded frame deltas while replaying.
+    pub fn time(&self) -> Duration {self.time}
+
+    /// Reports the area of the caret of the focused text input, see [`ImeEvent`](events::ImeEvent).
+    ///
+    /// Call it while building the focused text input, `offset` is relative to the node being built.
+    /// The area is cleared after a layout pass in which no node called it.
+    /// While an area is reported, typing keys go to the text input instead of unscoped shortcuts.
+    ///
+    /// Input methods are not supported by the window yet: maverick_os neither enables them
+    /// nor forwards their events, so the area is not passed on to the platform.
+    /// It can be read back with [`Headless::ime_area`](headless::Headless::ime_area).
+    pub fn set_ime_area(&mut self, offset: (f32, f32), size: (f32, f32)) {
+        let origin = self.focus.origin();
+        self.ime_area = Some(((origin.0 + offset.0, origin.1 + offset.1), size));
+    }
+
+    /// Returns the [`PointerEnter`] or [`PointerLeave`] the component handling `event` should receive first, if any.
+    pub(crate) fn hover_transition(&mut self, event: &dyn Event) -> Option<Box<dyn Event>> {
+        let MouseEvent{position, ..} = event.downcast_ref::<MouseEvent>()?;
+        let path = self.focus.path();
+        match (position.is_some(), self.hovered.contains(path)) {
+            (true, false) => {
+                self.hovered.insert(path.to_vec());
+                Some(Box::new(PointerEnter))
+            },
+            (false, true) => {
+                self.hovered.remove(path);
+                Some(Box::new(PointerLeave))
+            },
+            _ => None
+        }
+    }
+
+    /// Keeps the node being built hovered if it was, hovered nodes that are not built anymore are forgotten.
+    pub(crate) fn keep_hover(&mut self) {
+        if let Some(path) = self.hovered.take(self.focus.path()) {
+            self.built_hovered.insert(path);
+        }
+    }
+
+    /// Follows the pointer, resets the cursor icon and triggers the [`DragEvent`]s of the drag in progress.
+    fn track_pointer(&mut self, event: &dyn Event) {
+        if let Some(PointerEvent{id, kind, state: PointerState::Pressed, ..}) = event.downcast_ref::<PointerEvent>() {
+            self.pressed_pointer = Some((*kind, *id));
+        }
+        let Some(MouseEvent{position: Some(position), state}) = event.downcast_ref::<MouseEvent>() else { return };
+        self.cursor = *position;
+        self.cursor_icon = None;
+        if let Some(drag) = &mut self.drag {
+            drag.position = *position;
+            let state = match state {
+                MouseState::Moved | MouseState::Scroll(..) => DragState::Moved,
+                MouseState::Released => DragState::Dropped,
+                MouseState::Pressed => return,
+            };
+            let payload = drag.payload.clone();
+            self.trigger_event(DragEvent{position: Some(*position), state, payload});
+        }
+    }
+
+    /// Binds `shortcut` to `command`, so pressing it triggers a [`CommandEvent`] instead of the [`KeyboardEvent`].
+    ///
+    /// With a `scope`, the shortcut is only active while the focused node is the node
+    /// registered with that id, through [`Context::register_focusable`] or
+    /// [`Context::register_shortcut_scope`], or one of its descendants.
+    /// When several shortcuts match, the one with the innermost scope wins and global shortcuts come last.
+    ///
+    /// Plain character keys go to a focused [`TextInput`](emitters::TextInput) instead of global shortcuts.
+    ///
+    /// Returns an error if the shortcut is already bound in the same scope, or in another scope
+    /// registered on the same node in the last layout pass, as neither would take precedence.
+    pub fn register_shortcut(&mut self, shortcut: Shortcut, command: impl Into<String>, scope: Option<uuid::Uuid>) -> Result<(), ShortcutConflict> {
+        let focus = &self.focus;
+        self.shortcuts.register(shortcut, scope, command.into(), |a, b|
+            focus.scope_path(a).is_some_and(|path| focus.scope_path(b) == Some(path))
+        )
+    }
+
+    /// Removes the binding of `shortcut` in `scope`.
+    pub fn unregister_shortcut(&mut self, shortcut: &Shortcut, scope: Option<uuid::Uuid>) {
+        self.shortcuts.unregister(shortcut, scope);
+    }
+
+    /// Registers the node being built as a scope for shortcuts, see [`Context::register_shortcut`].
+    ///
+    /// Must be called from [`Component::build`] on every layout pass.
+    pub fn register_shortcut_scope(&mut self, id: uuid::Uuid) {
+        self.focus.register_scope(id);
+    }
+
+    /// Triggers the [`CommandEvent`] of the active shortcut matching a key press, returning `true` if there was one.
+    fn match_shortcut(&mut self, event: &dyn Event) -> bool {
+        let Some(KeyboardEvent{key, state: KeyboardState::Pressed, modifiers, ..}) = event.downcast_ref::<KeyboardEvent>() else { return false };
+        //A focused text input reports its caret, it takes the keys that type text
+        let typing = self.ime_area.is_some() && !modifiers.control && !modifiers.alt && !modifiers.meta
+            && matches!(key, Key::Character(_) | Key::Named(NamedKey::Space));
+        let command = self.shortcuts.matching(key, *modifiers).filter_map(|(_, scope, command)| match scope {
+            Some(scope) => self.focus.scope_depth(*scope).map(|depth| (depth + 1, command)),
+            None => (!typing).then_some((0, command)),
+        }).max_by_key(|(depth, _)| *depth).map(|(_, command)| command.clone());
+        match command {
+            Some(command) => {
+                self.trigger_event(CommandEvent{command});
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Handles Tab and Shift+Tab, returning `true` if the event moved the focus.
+    fn traverse_focus(&mut self, event: &dyn Event) -> bool {
+        match event.downcast_ref::<KeyboardEvent>() {
+            Some(KeyboardEvent{key: Key::Named(NamedKey::Tab), state: KeyboardState::Pressed, modifiers, ..}) => {
+                match self.focus.next(modifiers.shift) {
+                    Some(id) => {
+                        self.focus(id);
+                        true
+                    },
+                    None => false
+                }
+            },
+            _ => false
+        }
+    }
+
   //pub fn state(&mut self) -> &mut State {
   //    self.base_context.state()
   //}
@@ -240,6 +676,173 @@ pub trait Application {
     fn plugins(_ctx: &mut Context) -> Vec<Box<dyn Plugin>> { vec![] }
 }
 
+/// Drives a root [`Drawable`]: turns input into events, dispatches them,
+/// and lays out and draws the tree once per frame.
+///
+/// Shared by the windowed [`__private::PelicanEngine`] and [`headless::Headless`].
+pub(crate) struct Root {
+    pub(crate) context: Context,
+    pub(crate) scale: Scale,
+    pub(crate) screen: (f32, f32),
+    application: Box<dyn Drawable>,
+    sized_app: SizedBranch,
+    event_handler: EventHandler,
+    last_frame: Option<Instant>,
+}
+
+impl Root {
+    /// Lays out the `application` for the logical `screen` size.
+    pub(crate) fn new(context: Context, application: Box<dyn Drawable>, scale: Scale, screen: (f32, f32)) -> Self {
+        let mut root = Root {
+            context,
+            scale,
+            screen,
+            application,
+            sized_app: SizedBranch::default(),
+            event_handler: EventHandler::new(),
+            last_frame: None,
+        };
+        root.build();
+        root
+    }
+
+    /// Runs the size request and build passes, registering the focusable nodes again.
+    ///
+    /// The root is entered as the only child of the top level, so the focus paths
+    /// match the path followed by [`Root::dispatch`].
+    fn build(&mut self) {
+        self.context.focus.clear();
+        self.context.ime_area = None;
+        self.context.focus.enter(0, (0.0, 0.0));
+        let size_request = _Drawable::request_size(&self.application, &mut self.context);
+        self.sized_app = self.application.build(&mut self.context, self.screen, size_request);
+        self.context.focus.exit();
+        self.context.hovered = std::mem::take(&mut self.context.built_hovered);
+    }
+
+    /// Dispatches an event to the root drawable.
+    ///
+    /// Events that bubbled all the way up are dropped.
+    fn dispatch(&mut self, event: Box<dyn Event>) {
+        self.context.bubbling.clear();
+        self.context.focus.enter(0, (0.0, 0.0));
+        self.application.event(&mut self.context, self.sized_app.clone(), event);
+        self.context.focus.exit();
+        self.context.bubbling.clear();
+    }
+
+    /// Updates the scale factor and the logical screen size from a physical size.
+    pub(crate) fn resize(&mut self, scale_factor: f64, size: (u32, u32)) {
+        self.scale.0 = scale_factor;
+        self.screen = (self.scale.logical(size.0 as f32), self.scale.logical(size.1 as f32));
+    }
+
+    /// Returns the pointers that are currently pressed.
+    pub(crate) fn pointers(&self) -> &[Pointer] {
+        self.event_handler.pointers()
+    }
+
+    /// Returns the cursor icon requested while handling the last mouse event.
+    pub(crate) fn cursor(&self) -> CursorIcon {
+        self.context.cursor_icon.unwrap_or_default()
+    }
+
+    /// Returns the caret area reported with [`Context::set_ime_area`] during the last layout pass.
+    pub(crate) fn ime_area(&self) -> Option<((f32, f32), (f32, f32))> {
+        self.context.ime_area
+    }
+
+    /// Returns the geometry tree computed by the last layout pass.
+    pub(crate) fn layout(&self) -> LayoutNode {
+        self.application.layout_node((0.0, 0.0), &self.sized_app)
+    }
+
+    /// Converts window input into an event and queues it for the next frame.
+    ///
+    /// Window input is ignored while a [`Recording`] is replayed.
+    pub(crate) fn input(&mut self, input: Input) {
+        if let Some(input) = RawInput::from_input(input) && !self.context.is_replaying() {
+            self.raw_input(input);
+        }
+    }
+
+    /// Converts [`RawInput`] into an event and queues it for the next frame.
+    pub(crate) fn raw_input(&mut self, input: RawInput) {
+        if let Some(recorder) = &mut self.context.recorder {
+            recorder.push(Step::Input(input.clone()));
+        }
+        let events = self.event_handler.on_raw_input(&self.scale, input);
+        self.context.events.extend(events);
+    }
+
+    /// Runs a single frame: ticks, dispatches queued events, rebuilds the layout
+    /// and returns the physical canvas items to draw.
+    ///
+    /// The tick advances by `delta`, or by the time since the previous frame when `None`,
+    /// at most [`MAX_FRAME_DELTA`]. Replayed recordings advance by the recorded deltas.
+    pub(crate) fn frame(&mut self, delta: Option<Duration>) -> Vec<(CanvasArea, CanvasItem)> {
+        let now = Instant::now();
+        let elapsed = self.last_frame.map(|last| now - last).unwrap_or_default().min(MAX_FRAME_DELTA);
+        self.last_frame = Some(now);
+        let (replayed, recorded) = self.context.replayer.as_mut().map(Replayer::next_frame).unwrap_or_default();
+        let delta = recorded.or(delta).unwrap_or(elapsed);
+        if self.context.replayer.as_ref().is_some_and(Replayer::is_finished) {
+            self.context.replayer = None;
+        }
+        for input in replayed {
+            self.raw_input(input);
+        }
+        if let Some(recorder) = &mut self.context.recorder {
+            recorder.push(Step::Frame(delta));
+        }
+
+        let events = self.event_handler.on_input(&self.scale, Input::Tick);
+        self.context.events.extend(events);
+        self.context.time += delta;
+        self.dispatch(Box::new(TickEvent{delta}));
+
+        while let Some(event) = self.context.events.pop_front() {
+            if self.context.match_shortcut(&*event) || self.context.traverse_focus(&*event) { continue; }
+            self.context.track_pointer(&*event);
+            let released = Capture::released(&*event);
+            let dropped = matches!(event.downcast_ref::<DragEvent>(), Some(DragEvent{state: DragState::Dropped, ..}));
+            if let Some(event) = event
+                .pass(&mut self.context, &vec![((0.0, 0.0), self.sized_app.0)])
+                .remove(0)
+            {
+                for id in self.context.plugins.keys().copied().collect::<Vec<_>>() {
+                    let mut plugin = self.context.plugins.remove(&id).unwrap();
+                    plugin.event(&mut self.context, &*event);    
+                    self.context.plugins.insert(id, plugin);
+                }
+                self.dispatch(event);
+            }
+            if let Some(released) = released
+                && self.context.pointer_capture.as_mut().is_some_and(|capture| capture.release(released))
+            {
+                self.context.release_pointer();
+            }
+            if dropped {
+                self.context.drag = None;
+            }
+        }
+
+        self.build();
+        let bound = (0.0, 0.0, self.screen.0, self.screen.1);
+        let mut drawn = self.application.draw(self.sized_app.clone(), (0.0, 0.0), bound);
+        if let Some(mut drag) = self.context.drag.take() {
+            if let Some(preview) = &mut drag.preview {
+                let request = _Drawable::request_size(&preview.drawable, &mut self.context);
+                let sized = preview.drawable.build(&mut self.context, preview.size, request);
+                let offset = (drag.position.0 - preview.anchor.0, drag.position.1 - preview.anchor.1);
+                drawn.extend(preview.drawable.draw(sized, offset, bound));
+            }
+            self.context.drag = Some(drag);
+        }
+        drawn.into_iter().map(|(a, i)| (a.scale(&self.scale), i.scale(&self.scale))).collect()
+    }
+}
+
 #[doc(hidden)]
 pub mod __private {
     use std::sync::Arc;
@@ -248,8 +851,7 @@ pub mod __private {
     use maverick_os::window::{Window, Event as WindowEvent};
     pub use maverick_os::{HardwareContext, RuntimeContext, ServiceList, Services, start as maverick_start};
     
-    use crate::{_Drawable, Application, Canvas, CanvasItem, Context, Drawable, EventHandler, Lifetime, Scale, SizedBranch, TickEvent};
-    use crate::layout::Scaling;
+    use crate::{Application, Canvas, CanvasItem, Context, CursorIcon, Lifetime, Root, Scale};
 
     /// Provide [`Services`] for [`PelicanEngine`] by deferring to the application type.
     impl<A: Application> Services for PelicanEngine<A> {
@@ -262,14 +864,10 @@ pub mod __private {
     /// plugin management, drawing, and event handling.
     pub struct PelicanEngine<A: Application> {
         _p: std::marker::PhantomData<A>,
-        scale: Scale,
         canvas: Canvas,
-        screen: (f32, f32),
-        context: Context,
-        sized_app: SizedBranch,
-        application: Box<dyn Drawable>,
-        event_handler: EventHandler,
+        root: Root,
         items: Vec<(Area, CanvasItem)>,
+        cursor: CursorIcon,
     }
 
     impl<A: Application> maverick_os::Application for PelicanEngine<A> {
@@ -287,81 +885,53 @@ pub mod __private {
             let mut context = Context::new(ctx.hardware.clone(), ctx.runtime.clone(), ctx.state.take());
             let plugins = A::plugins(&mut context);
             context.plugins = plugins.into_iter().map(|p| ((*p).type_id(), p)).collect();
-            let mut application = A::new(&mut context).await;
-            let size_request = _Drawable::request_size(&application, &mut context);
-            let sized_app = application.build(&mut context, screen, size_request);
-            ctx.state = context.state.take();
+            let application = A::new(&mut context).await;
+            let mut root = Root::new(context, Box::new(application), scale, screen);
+            ctx.state = root.context.state.take();
             PelicanEngine{
                 _p: std::marker::PhantomData::<A>,
-                scale,
                 canvas,
-                screen,
-                context,
-                sized_app,
-                application: Box::new(application),
-                event_handler: EventHandler::new(),
-                items: Vec::new()
+                root,
+                items: Vec::new(),
+                cursor: CursorIcon::Default,
             }
         }
             
         async fn on_event(&mut self, context: &mut maverick_os::Context, event: WindowEvent) {
-            self.context.state = context.state.take();
+            self.root.context.state = context.state.take();
             match event {
                 WindowEvent::Lifetime(lifetime) => match lifetime {
                     Lifetime::Resized => {
-                        self.scale.0 = context.window.scale_factor;
                         let size = context.window.size;
                         let size = self.canvas.resize::<Arc<Window>>(None, size.0, size.1);
-                        let size = (self.scale.logical(size.0 as f32), self.scale.logical(size.1 as f32));
-                        self.screen = size;
+                        self.root.resize(context.window.scale_factor, size);
                     },
                     Lifetime::Resumed => {
                         let _ = self.items.drain(..);
-                        self.scale.0 = context.window.scale_factor;
                         let size = context.window.size;
                         let size = self.canvas.resize(Some(context.window.handle.clone()), size.0, size.1);
-                        let size = (self.scale.logical(size.0 as f32), self.scale.logical(size.1 as f32));
-                        self.screen = size;
+                        self.root.resize(context.window.scale_factor, size);
                     },
                     Lifetime::Paused => {},
                     Lifetime::Close => {},
                     Lifetime::Draw => {//Size before events because the events are given between
                                     //resizing
-
-                        let result = self.event_handler.on_input(&self.scale, maverick_os::window::Input::Tick);
-                        if let Some(event) = result {
-                            self.context.events.push_back(event);
-                        }
-                        self.application.event(&mut self.context, self.sized_app.clone(), Box::new(TickEvent));
-
-                        while let Some(event) = self.context.events.pop_front() {
-                            if let Some(event) = event
-                                .pass(&mut self.context, &vec![((0.0, 0.0), self.sized_app.0)])
-                                .remove(0)
-                            {
-                                for id in self.context.plugins.keys().copied().collect::<Vec<_>>() {
-                                    let mut plugin = self.context.plugins.remove(&id).unwrap();
-                                    plugin.event(&mut self.context, &*event);    
-                                    self.context.plugins.insert(id, plugin);
-                                }
-                                self.application.event(&mut self.context, self.sized_app.clone(), event);
-                            }
-                        }
-
-                        let size_request = _Drawable::request_size(&*self.application, &mut self.context);
-                        self.sized_app = self.application.build(&mut self.context, self.screen, size_request);
-                        let drawn = self.application.draw(self.sized_app.clone(), (0.0, 0.0), (0.0, 0.0, self.screen.0, self.screen.1));
-                        let items: Vec<_> = drawn.into_iter().map(|(a, i)| (a.scale(&self.scale), i.scale(&self.scale))).collect();
+                        let items = self.root.frame(None);
                         if self.items != items {
                             self.items = items.clone();
-                            self.canvas.draw(&mut self.context.assets.atlas, items);
+                            self.canvas.draw(&mut self.root.context.assets.atlas, items);
+                        }
+                        let cursor = self.root.cursor();
+                        if self.cursor != cursor {
+                            self.cursor = cursor;
+                            context.window.handle.set_cursor(cursor);
                         }
                     },
                     Lifetime::MemoryWarning => {},
                 },
-                WindowEvent::Input(input) => {if let Some(event) = self.event_handler.on_input(&self.scale, input) {self.context.events.push_back(event)}}
+                WindowEvent::Input(input) => self.root.input(input),
             }
-            context.state = self.context.state.take();
+            context.state = self.root.context.state.take();
         }
     }
 }
@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::{RawInput, ImeEvent, PointerKind, Modifiers, Key, NamedKey, SmolStr, TouchPhase, ElementState, MouseScrollDelta};
+
+/// A single step of a [`Recording`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Input received from the window.
+    Input(RawInput),
+    /// A frame was drawn, advancing the time by the [`TickEvent::delta`](crate::events::TickEvent).
+    Frame(Duration),
+}
+
+/// A recorded input session.
+///
+/// Holds every input and frame in the order they happened,
+/// each with the time elapsed since the recording started.
+/// When replayed, each frame is ticked with the [`TickEvent::delta`](crate::events::TickEvent) it was recorded with.
+///
+/// Saving fails if the recording holds a key the platform could not identify.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(try_from = "Vec<(Duration, Saved)>")]
+pub struct Recording(pub Vec<(Duration, Step)>);
+
+impl Recording {
+    /// Writes the recording to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        Ok(serde_json::to_writer(BufWriter::new(File::create(path)?), self)?)
+    }
+
+    /// Reads a recording written by [`Recording::save`].
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
+
+impl Serialize for Recording {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.iter().map(|(time, step)| Ok((*time, match step {
+            Step::Input(input) => Saved::Input(SavedInput::from_raw(input.clone())?),
+            Step::Frame(delta) => Saved::Frame(*delta),
+        }))).collect::<Result<Vec<_>, String>>().map_err(serde::ser::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl TryFrom<Vec<(Duration, Saved)>> for Recording {
+    type Error = String;
+
+    fn try_from(steps: Vec<(Duration, Saved)>) -> Result<Self, String> {
+        steps.into_iter().map(|(time, step)| Ok((time, match step {
+            Saved::Input(input) => Step::Input(input.into_raw()?),
+            Saved::Frame(delta) => Step::Frame(delta),
+        }))).collect::<Result<_, String>>().map(Recording)
+    }
+}
+
+/// The saved form of a [`Step`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Saved {
+    Input(SavedInput),
+    Frame(Duration),
+}
+
+/// The saved form of [`RawInput`], independent of the window backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum SavedInput {
+    Touch { id: u64, kind: PointerKind, position: (f32, f32), phase: SavedPhase },
+    CursorMoved((f32, f32)),
+    Mouse { pressed: bool },
+    MouseWheel { delta: SavedScroll, phase: SavedPhase },
+    Keyboard { key: SavedKey, pressed: bool, #[serde(default)] text: Option<String> },
+    Modifiers(Modifiers),
+    Ime(ImeEvent),
+    Focused(bool),
+    Tick,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum SavedPhase {Started, Moved, Ended, Cancelled}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum SavedScroll {Line(f32, f32), Pixel(f64, f64)}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum SavedKey {
+    /// A [`NamedKey`] by its variant name.
+    Named(String),
+    Character(String),
+    Dead(Option<char>),
+}
+
+/// Every [`NamedKey`], so a recording can hold any of them.
+const NAMED_KEYS: &[NamedKey] = &[
+    NamedKey::Alt, NamedKey::AltGraph, NamedKey::CapsLock, NamedKey::Control, NamedKey::Fn, NamedKey::FnLock,
+    NamedKey::NumLock, NamedKey::ScrollLock, NamedKey::Shift, NamedKey::Symbol, NamedKey::SymbolLock, NamedKey::Meta,
+    NamedKey::Hyper, NamedKey::Super, NamedKey::Enter, NamedKey::Tab, NamedKey::Space, NamedKey::ArrowDown,
+    NamedKey::ArrowLeft, NamedKey::ArrowRight, NamedKey::ArrowUp, NamedKey::End, NamedKey::Home, NamedKey::PageDown,
+    NamedKey::PageUp, NamedKey::Backspace, NamedKey::Clear, NamedKey::Copy, NamedKey::CrSel, NamedKey::Cut,
+    NamedKey::Delete, NamedKey::EraseEof, NamedKey::ExSel, NamedKey::Insert, NamedKey::Paste, NamedKey::Redo,
+    NamedKey::Undo, NamedKey::Accept, NamedKey::Again, NamedKey::Attn, NamedKey::Cancel, NamedKey::ContextMenu,
+    NamedKey::Escape, NamedKey::Execute, NamedKey::Find, NamedKey::Help, NamedKey::Pause, NamedKey::Play,
+    NamedKey::Props, NamedKey::Select, NamedKey::ZoomIn, NamedKey::ZoomOut, NamedKey::BrightnessDown,
+    NamedKey::BrightnessUp, NamedKey::Eject, NamedKey::LogOff, NamedKey::Power, NamedKey::PowerOff,
+    NamedKey::PrintScreen, NamedKey::Hibernate, NamedKey::Standby, NamedKey::WakeUp, NamedKey::AllCandidates,
+    NamedKey::Alphanumeric, NamedKey::CodeInput, NamedKey::Compose, NamedKey::Convert, NamedKey::FinalMode,
+    NamedKey::GroupFirst, NamedKey::GroupLast, NamedKey::GroupNext, NamedKey::GroupPrevious, NamedKey::ModeChange,
+    NamedKey::NextCandidate, NamedKey::NonConvert, NamedKey::PreviousCandidate, NamedKey::Process,
+    NamedKey::SingleCandidate, NamedKey::HangulMode, NamedKey::HanjaMode, NamedKey::JunjaMode, NamedKey::Eisu,
+    NamedKey::Hankaku, NamedKey::Hiragana, NamedKey::HiraganaKatakana, NamedKey::KanaMode, NamedKey::KanjiMode,
+    NamedKey::Katakana, NamedKey::Romaji, NamedKey::Zenkaku, NamedKey::ZenkakuHankaku, NamedKey::Soft1,
+    NamedKey::Soft2, NamedKey::Soft3, NamedKey::Soft4, NamedKey::ChannelDown, NamedKey::ChannelUp, NamedKey::Close,
+    NamedKey::MailForward, NamedKey::MailReply, NamedKey::MailSend, NamedKey::MediaClose, NamedKey::MediaFastForward,
+    NamedKey::MediaPause, NamedKey::MediaPlay, NamedKey::MediaPlayPause, NamedKey::MediaRecord, NamedKey::MediaRewind,
+    NamedKey::MediaStop, NamedKey::MediaTrackNext, NamedKey::MediaTrackPrevious, NamedKey::New, NamedKey::Open,
+    NamedKey::Print, NamedKey::Save, NamedKey::SpellCheck, NamedKey::Key11, NamedKey::Key12,
+    NamedKey::AudioBalanceLeft, NamedKey::AudioBalanceRight, NamedKey::AudioBassBoostDown,
+    NamedKey::AudioBassBoostToggle, NamedKey::AudioBassBoostUp, NamedKey::AudioFaderFront, NamedKey::AudioFaderRear,
+    NamedKey::AudioSurroundModeNext, NamedKey::AudioTrebleDown, NamedKey::AudioTrebleUp, NamedKey::AudioVolumeDown,
+    NamedKey::AudioVolumeUp, NamedKey::AudioVolumeMute, NamedKey::MicrophoneToggle, NamedKey::MicrophoneVolumeDown,
+    NamedKey::MicrophoneVolumeUp, NamedKey::MicrophoneVolumeMute, NamedKey::SpeechCorrectionList,
+    NamedKey::SpeechInputToggle, NamedKey::LaunchApplication1, NamedKey::LaunchApplication2, NamedKey::LaunchCalendar,
+    NamedKey::LaunchContacts, NamedKey::LaunchMail, NamedKey::LaunchMediaPlayer, NamedKey::LaunchMusicPlayer,
+    NamedKey::LaunchPhone, NamedKey::LaunchScreenSaver, NamedKey::LaunchSpreadsheet, NamedKey::LaunchWebBrowser,
+    NamedKey::LaunchWebCam, NamedKey::LaunchWordProcessor, NamedKey::BrowserBack, NamedKey::BrowserFavorites,
+    NamedKey::BrowserForward, NamedKey::BrowserHome, NamedKey::BrowserRefresh, NamedKey::BrowserSearch,
+    NamedKey::BrowserStop, NamedKey::AppSwitch, NamedKey::Call, NamedKey::Camera, NamedKey::CameraFocus,
+    NamedKey::EndCall, NamedKey::GoBack, NamedKey::GoHome, NamedKey::HeadsetHook, NamedKey::LastNumberRedial,
+    NamedKey::Notification, NamedKey::MannerMode, NamedKey::VoiceDial, NamedKey::TV, NamedKey::TV3DMode,
+    NamedKey::TVAntennaCable, NamedKey::TVAudioDescription, NamedKey::TVAudioDescriptionMixDown,
+    NamedKey::TVAudioDescriptionMixUp, NamedKey::TVContentsMenu, NamedKey::TVDataService, NamedKey::TVInput,
+    NamedKey::TVInputComponent1, NamedKey::TVInputComponent2, NamedKey::TVInputComposite1,
+    NamedKey::TVInputComposite2, NamedKey::TVInputHDMI1, NamedKey::TVInputHDMI2, NamedKey::TVInputHDMI3,
+    NamedKey::TVInputHDMI4, NamedKey::TVInputVGA1, NamedKey::TVMediaContext, NamedKey::TVNetwork,
+    NamedKey::TVNumberEntry, NamedKey::TVPower, NamedKey::TVRadioService, NamedKey::TVSatellite,
+    NamedKey::TVSatelliteBS, NamedKey::TVSatelliteCS, NamedKey::TVSatelliteToggle, NamedKey::TVTerrestrialAnalog,
+    NamedKey::TVTerrestrialDigital, NamedKey::TVTimer, NamedKey::AVRInput, NamedKey::AVRPower, NamedKey::ColorF0Red,
+    NamedKey::ColorF1Green, NamedKey::ColorF2Yellow, NamedKey::ColorF3Blue, NamedKey::ColorF4Grey,
+    NamedKey::ColorF5Brown, NamedKey::ClosedCaptionToggle, NamedKey::Dimmer, NamedKey::DisplaySwap, NamedKey::DVR,
+    NamedKey::Exit, NamedKey::FavoriteClear0, NamedKey::FavoriteClear1, NamedKey::FavoriteClear2,
+    NamedKey::FavoriteClear3, NamedKey::FavoriteRecall0, NamedKey::FavoriteRecall1, NamedKey::FavoriteRecall2,
+    NamedKey::FavoriteRecall3, NamedKey::FavoriteStore0, NamedKey::FavoriteStore1, NamedKey::FavoriteStore2,
+    NamedKey::FavoriteStore3, NamedKey::Guide, NamedKey::GuideNextDay, NamedKey::GuidePreviousDay, NamedKey::Info,
+    NamedKey::InstantReplay, NamedKey::Link, NamedKey::ListProgram, NamedKey::LiveContent, NamedKey::Lock,
+    NamedKey::MediaApps, NamedKey::MediaAudioTrack, NamedKey::MediaLast, NamedKey::MediaSkipBackward,
+    NamedKey::MediaSkipForward, NamedKey::MediaStepBackward, NamedKey::MediaStepForward, NamedKey::MediaTopMenu,
+    NamedKey::NavigateIn, NamedKey::NavigateNext, NamedKey::NavigateOut, NamedKey::NavigatePrevious,
+    NamedKey::NextFavoriteChannel, NamedKey::NextUserProfile, NamedKey::OnDemand, NamedKey::Pairing,
+    NamedKey::PinPDown, NamedKey::PinPMove, NamedKey::PinPToggle, NamedKey::PinPUp, NamedKey::PlaySpeedDown,
+    NamedKey::PlaySpeedReset, NamedKey::PlaySpeedUp, NamedKey::RandomToggle, NamedKey::RcLowBattery,
+    NamedKey::RecordSpeedNext, NamedKey::RfBypass, NamedKey::ScanChannelsToggle, NamedKey::ScreenModeNext,
+    NamedKey::Settings, NamedKey::SplitScreenToggle, NamedKey::STBInput, NamedKey::STBPower, NamedKey::Subtitle,
+    NamedKey::Teletext, NamedKey::VideoModeNext, NamedKey::Wink, NamedKey::ZoomToggle, NamedKey::F1, NamedKey::F2,
+    NamedKey::F3, NamedKey::F4, NamedKey::F5, NamedKey::F6, NamedKey::F7, NamedKey::F8, NamedKey::F9, NamedKey::F10,
+    NamedKey::F11, NamedKey::F12, NamedKey::F13, NamedKey::F14, NamedKey::F15, NamedKey::F16, NamedKey::F17,
+    NamedKey::F18, NamedKey::F19, NamedKey::F20, NamedKey::F21, NamedKey::F22, NamedKey::F23, NamedKey::F24,
+    NamedKey::F25, NamedKey::F26, NamedKey::F27, NamedKey::F28, NamedKey::F29, NamedKey::F30, NamedKey::F31,
+    NamedKey::F32, NamedKey::F33, NamedKey::F34, NamedKey::F35,
+];
+
+impl From<TouchPhase> for SavedPhase {
+    fn from(phase: TouchPhase) -> Self {
+        match phase {
+            TouchPhase::Started => SavedPhase::Started,
+            TouchPhase::Moved => SavedPhase::Moved,
+            TouchPhase::Ended => SavedPhase::Ended,
+            TouchPhase::Cancelled => SavedPhase::Cancelled,
+        }
+    }
+}
+
+impl From<SavedPhase> for TouchPhase {
+    fn from(phase: SavedPhase) -> Self {
+        match phase {
+            SavedPhase::Started => TouchPhase::Started,
+            SavedPhase::Moved => TouchPhase::Moved,
+            SavedPhase::Ended => TouchPhase::Ended,
+            SavedPhase::Cancelled => TouchPhase::Cancelled,
+        }
+    }
+}
+
+impl SavedInput {
+    /// Fails on keys the platform could not identify.
+    fn from_raw(input: RawInput) -> Result<Self, String> {
+        Ok(match input {
+            RawInput::Touch{id, kind, position, phase} => SavedInput::Touch{id, kind, position, phase: phase.into()},
+            RawInput::CursorMoved(position) => SavedInput::CursorMoved(position),
+            RawInput::Mouse(state) => SavedInput::Mouse{pressed: state == ElementState::Pressed},
+            RawInput::MouseWheel{delta, phase} => SavedInput::MouseWheel{phase: phase.into(), delta: match delta {
+                MouseScrollDelta::LineDelta(x, y) => SavedScroll::Line(x, y),
+                MouseScrollDelta::PixelDelta(p) => SavedScroll::Pixel(p.x, p.y),
+            }},
+            RawInput::Keyboard{key, state, text} => SavedInput::Keyboard{
+                key: match key {
+                    Key::Named(named) => SavedKey::Named(format!("{named:?}")),
+                    Key::Character(c) => SavedKey::Character(c.to_string()),
+                    Key::Dead(c) => SavedKey::Dead(c),
+                    Key::Unidentified(native) => return Err(format!("cannot save the unidentified key {native:?}")),
+                },
+                pressed: state == ElementState::Pressed,
+                text: text.map(|t| t.to_string()),
+            },
+            RawInput::Modifiers(modifiers) => SavedInput::Modifiers(modifiers),
+            RawInput::Ime(ime) => SavedInput::Ime(ime),
+            RawInput::Focused(focused) => SavedInput::Focused(focused),
+            RawInput::Tick => SavedInput::Tick,
+        })
+    }
+
+    fn into_raw(self) -> Result<RawInput, String> {
+        let state = |pressed| if pressed {ElementState::Pressed} else {ElementState::Released};
+        Ok(match self {
+            SavedInput::Touch{id, kind, position, phase} => RawInput::Touch{id, kind, position, phase: phase.into()},
+            SavedInput::CursorMoved(position) => RawInput::CursorMoved(position),
+            SavedInput::Mouse{pressed} => RawInput::Mouse(state(pressed)),
+            SavedInput::MouseWheel{delta, phase} => RawInput::MouseWheel{phase: phase.into(), delta: match delta {
+                SavedScroll::Line(x, y) => MouseScrollDelta::LineDelta(x, y),
+                SavedScroll::Pixel(x, y) => MouseScrollDelta::PixelDelta(dpi::PhysicalPosition::new(x, y)),
+            }},
+            SavedInput::Keyboard{key, pressed, text} => RawInput::Keyboard{
+                key: match key {
+                    SavedKey::Named(name) => Key::Named(*NAMED_KEYS.iter().find(|named| format!("{named:?}") == name)
+                        .ok_or_else(|| format!("unknown key {name}"))?),
+                    SavedKey::Character(c) => Key::Character(SmolStr::new(c)),
+                    SavedKey::Dead(c) => Key::Dead(c),
+                },
+                state: state(pressed),
+                text: text.map(SmolStr::new),
+            },
+            SavedInput::Modifiers(modifiers) => RawInput::Modifiers(modifiers),
+            SavedInput::Ime(ime) => RawInput::Ime(ime),
+            SavedInput::Focused(focused) => RawInput::Focused(focused),
+            SavedInput::Tick => RawInput::Tick,
+        })
+    }
+}
+
+pub(crate) struct Recorder {
+    start: Instant,
+    recording: Recording,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder{start: Instant::now(), recording: Recording::default()}
+    }
+
+    pub fn push(&mut self, step: Step) {
+        self.recording.0.push((self.start.elapsed(), step));
+    }
+
+    pub fn finish(self) -> Recording {self.recording}
+}
+
+pub(crate) struct Replayer {
+    steps: VecDeque<(Duration, Step)>,
+}
+
+impl Replayer {
+    pub fn new(recording: Recording) -> Self {Replayer{steps: recording.0.into()}}
+
+    /// Returns the inputs recorded before the next frame and consumes that frame,
+    /// along with the delta it was recorded with.
+    pub fn next_frame(&mut self) -> (Vec<RawInput>, Option<Duration>) {
+        let mut inputs = Vec::new();
+        while let Some((_, step)) = self.steps.pop_front() {
+            match step {
+                Step::Input(input) => inputs.push(input),
+                Step::Frame(delta) => return (inputs, Some(delta)),
+            }
+        }
+        (inputs, None)
+    }
+
+    pub fn is_finished(&self) -> bool {self.steps.is_empty()}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Context;
+    use crate::drawable::{Shape, ShapeType, Color};
+    use crate::emitters::Button;
+    use crate::headless::{Headless, Probe, ProbeLog};
+
+    fn app() -> (Headless, ProbeLog) {
+        let probe = Probe::new(Shape::new(ShapeType::Rectangle(0.0, (50.0, 50.0), 0.0), Color(0, 0, 0, 255)));
+        let log = probe.log();
+        (Headless::with_root(Context::headless(None), Button::new(probe), (100, 100), 1.0), log)
+    }
+
+    #[test]
+    fn recordings_survive_a_save_and_load() {
+        let recording = Recording(vec![
+            (Duration::from_millis(1), Step::Input(RawInput::CursorMoved((4.0, 5.0)))),
+            (Duration::from_millis(2), Step::Input(RawInput::Keyboard{
+                key: Key::Named(NamedKey::MediaPlayPause), state: ElementState::Pressed, text: None
+            })),
+            (Duration::from_millis(3), Step::Input(RawInput::Keyboard{
+                key: Key::Character(SmolStr::new("a")), state: ElementState::Released, text: Some(SmolStr::new("a"))
+            })),
+            (Duration::from_millis(4), Step::Input(RawInput::Modifiers(Modifiers{shift: true, ..Modifiers::default()}))),
+            (Duration::from_millis(4), Step::Input(RawInput::Ime(ImeEvent::Preedit("ka".into(), Some((0, 2)))))),
+            (Duration::from_millis(5), Step::Frame(Duration::from_millis(7))),
+        ]);
+        let path = std::env::temp_dir().join(format!("roost_recording_{}.json", std::process::id()));
+        recording.save(&path).unwrap();
+        let loaded = Recording::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), recording);
+    }
+
+    #[test]
+    fn replays_are_deterministic() {
+        let (mut original, original_log) = app();
+        original.context().start_recording();
+        original.click((10.0, 10.0));
+        original.advance(Duration::from_millis(5));
+        original.move_to((80.0, 80.0));
+        original.frame();
+        let recording = original.context().stop_recording().unwrap();
+        assert!(original_log.contains("Pressed(true)"));
+
+        for _ in 0..2 {
+            let (mut replayed, log) = app();
+            replayed.replay(recording.clone());
+            assert_eq!(log.events(), original_log.events());
+            assert_eq!(replayed.context().time(), original.context().time());
+            assert!(!replayed.context().is_replaying());
+        }
+    }
+}
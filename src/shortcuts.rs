@@ -0,0 +1,212 @@
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::events::{Key, Modifiers, NamedKey, SmolStr};
+
+/// A key chord such as Ctrl+S or Cmd+Shift+P.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl Shortcut {
+    pub fn new(key: Key, modifiers: Modifiers) -> Self {
+        Shortcut{key: normalize(key), modifiers}
+    }
+
+    /// Parses a chord like `"Ctrl+Shift+P"`, `"Cmd+S"` or `"Alt+ArrowLeft"`.
+    ///
+    /// The modifiers are `Shift`, `Ctrl`, `Alt` and `Cmd`, `Meta` or `Super`.
+    /// `Mod` stands for Cmd on Apple platforms and Ctrl elsewhere.
+    /// The key is either a single character or the name of a [`NamedKey`].
+    pub fn parse(chord: &str) -> Option<Self> {
+        if chord.trim().is_empty() {return None;}
+        let mut modifiers = Modifiers::default();
+        let mut parts = chord.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = match parts.pop()? {
+            "" => "+",
+            key => key,
+        };
+        for part in parts.into_iter().filter(|part| !part.is_empty()) {
+            match part.to_lowercase().as_str() {
+                "shift" => modifiers.shift = true,
+                "ctrl" | "control" => modifiers.control = true,
+                "alt" | "option" => modifiers.alt = true,
+                "cmd" | "command" | "meta" | "super" => modifiers.meta = true,
+                "mod" if Modifiers::APPLE => modifiers.meta = true,
+                "mod" => modifiers.control = true,
+                _ => return None,
+            }
+        }
+        let key = match key.chars().count() {
+            1 => Key::Character(SmolStr::new(key)),
+            _ => Key::Named(named_key(key)?),
+        };
+        Some(Shortcut::new(key, modifiers))
+    }
+
+    /// Returns `true` if pressing `key` with `modifiers` triggers this shortcut.
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        self.modifiers == modifiers && self.key == normalize(key.clone())
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Modifiers{shift, control, alt, meta} = self.modifiers;
+        let names = [(control, "Ctrl"), (alt, "Alt"), (shift, "Shift"), (meta, "Cmd")];
+        for (_, name) in names.into_iter().filter(|(pressed, _)| *pressed) {
+            write!(f, "{name}+")?;
+        }
+        match &self.key {
+            Key::Character(c) => write!(f, "{}", c.to_uppercase()),
+            Key::Named(named) => write!(f, "{named:?}"),
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+/// Returned by [`Context::register_shortcut`](crate::Context::register_shortcut)
+/// when the shortcut is already bound with the same precedence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutConflict {
+    pub shortcut: Shortcut,
+    /// The command the shortcut is already bound to.
+    pub command: String,
+}
+
+impl fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is already bound to {:?}", self.shortcut, self.command)
+    }
+}
+
+impl std::error::Error for ShortcutConflict {}
+
+/// Characters are compared in lowercase, so Shift+S matches regardless of the reported case.
+fn normalize(key: Key) -> Key {
+    match key {
+        Key::Character(c) => Key::Character(SmolStr::new(c.to_lowercase())),
+        key => key,
+    }
+}
+
+fn named_key(name: &str) -> Option<NamedKey> {
+    Some(match name.to_lowercase().as_str() {
+        "enter" | "return" => NamedKey::Enter,
+        "tab" => NamedKey::Tab,
+        "space" => NamedKey::Space,
+        "backspace" => NamedKey::Backspace,
+        "delete" => NamedKey::Delete,
+        "escape" | "esc" => NamedKey::Escape,
+        "arrowup" | "up" => NamedKey::ArrowUp,
+        "arrowdown" | "down" => NamedKey::ArrowDown,
+        "arrowleft" | "left" => NamedKey::ArrowLeft,
+        "arrowright" | "right" => NamedKey::ArrowRight,
+        "home" => NamedKey::Home,
+        "end" => NamedKey::End,
+        "pageup" => NamedKey::PageUp,
+        "pagedown" => NamedKey::PageDown,
+        "f1" => NamedKey::F1,
+        "f2" => NamedKey::F2,
+        "f3" => NamedKey::F3,
+        "f4" => NamedKey::F4,
+        "f5" => NamedKey::F5,
+        "f6" => NamedKey::F6,
+        "f7" => NamedKey::F7,
+        "f8" => NamedKey::F8,
+        "f9" => NamedKey::F9,
+        "f10" => NamedKey::F10,
+        "f11" => NamedKey::F11,
+        "f12" => NamedKey::F12,
+        _ => return None,
+    })
+}
+
+/// The shortcuts registered on a [`Context`](crate::Context), each bound to a command
+/// and optionally scoped to a node.
+#[derive(Debug, Default)]
+pub(crate) struct Shortcuts(Vec<(Shortcut, Option<Uuid>, String)>);
+
+impl Shortcuts {
+    /// Adds a binding, failing if the shortcut is bound in the same scope
+    /// or in a scope that `same_node` reports to be registered on the same node.
+    pub fn register(
+        &mut self, shortcut: Shortcut, scope: Option<Uuid>, command: String, same_node: impl Fn(Uuid, Uuid) -> bool
+    ) -> Result<(), ShortcutConflict> {
+        let conflicts = |other: &Option<Uuid>| match (other, scope) {
+            (Some(a), Some(b)) => *a == b || same_node(*a, b),
+            (a, b) => *a == b,
+        };
+        if let Some((_, _, command)) = self.0.iter().find(|(s, sc, _)| *s == shortcut && conflicts(sc)) {
+            return Err(ShortcutConflict{shortcut, command: command.clone()});
+        }
+        self.0.push((shortcut, scope, command));
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, shortcut: &Shortcut, scope: Option<Uuid>) {
+        self.0.retain(|(s, sc, _)| s != shortcut || *sc != scope);
+    }
+
+    /// Returns the shortcuts matching `key` and `modifiers`, with their scope and command.
+    pub fn matching(&self, key: &Key, modifiers: Modifiers) -> impl Iterator<Item = &(Shortcut, Option<Uuid>, String)> {
+        self.0.iter().filter(move |(s, ..)| s.matches(key, modifiers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl() -> Modifiers {Modifiers{control: true, ..Modifiers::default()}}
+
+    #[test]
+    fn parse_reads_modifiers_and_keys() {
+        let shortcut = Shortcut::parse("Ctrl+Shift+P").unwrap();
+        assert_eq!(shortcut.key, Key::Character(SmolStr::new("p")));
+        assert_eq!(shortcut.modifiers, Modifiers{control: true, shift: true, ..Modifiers::default()});
+        assert_eq!(Shortcut::parse("alt + arrowleft").unwrap().key, Key::Named(NamedKey::ArrowLeft));
+        assert_eq!(Shortcut::parse("Cmd+Esc").unwrap(), Shortcut::new(Key::Named(NamedKey::Escape), Modifiers{meta: true, ..Modifiers::default()}));
+        assert_eq!(Shortcut::parse("Ctrl++").unwrap().key, Key::Character(SmolStr::new("+")));
+    }
+
+    #[test]
+    fn parse_maps_mod_to_the_platform_modifier() {
+        let modifiers = Shortcut::parse("Mod+S").unwrap().modifiers;
+        assert_eq!(modifiers.meta, Modifiers::APPLE);
+        assert_eq!(modifiers.control, !Modifiers::APPLE);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_names() {
+        assert_eq!(Shortcut::parse("Hyper+S"), None);
+        assert_eq!(Shortcut::parse("Ctrl+Banana"), None);
+        assert_eq!(Shortcut::parse(""), None);
+    }
+
+    #[test]
+    fn shortcuts_match_characters_in_any_case() {
+        let shortcut = Shortcut::parse("Ctrl+S").unwrap();
+        assert!(shortcut.matches(&Key::Character(SmolStr::new("S")), ctrl()));
+        assert!(!shortcut.matches(&Key::Character(SmolStr::new("s")), Modifiers::default()));
+        assert_eq!(shortcut.to_string(), "Ctrl+S");
+    }
+
+    #[test]
+    fn register_detects_conflicts() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let save = Shortcut::parse("Ctrl+S").unwrap();
+        let mut shortcuts = Shortcuts::default();
+        let apart = |_, _| false;
+        shortcuts.register(save.clone(), None, "save".into(), apart).unwrap();
+        assert_eq!(shortcuts.register(save.clone(), None, "other".into(), apart).unwrap_err().command, "save");
+        shortcuts.register(save.clone(), Some(a), "save a".into(), apart).unwrap();
+        assert!(shortcuts.register(save.clone(), Some(a), "other".into(), apart).is_err());
+        assert!(shortcuts.register(save.clone(), Some(b), "save b".into(), |x, y| (x, y) == (a, b)).is_err());
+        shortcuts.register(save.clone(), Some(b), "save b".into(), apart).unwrap();
+        assert_eq!(shortcuts.matching(&Key::Character(SmolStr::new("s")), ctrl()).count(), 3);
+    }
+}
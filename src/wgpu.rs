@@ -1,4 +1,4 @@
-use wgpu::{RenderPassDepthStencilAttachment, RenderPassColorAttachment, CommandEncoderDescriptor, TextureViewDescriptor, RequestAdapterOptions, SurfaceConfiguration, RenderPassDescriptor, InstanceDescriptor, DepthStencilState, TextureDescriptor, TextureDimension, MultisampleState, DeviceDescriptor, PowerPreference, CompareFunction, WindowHandle, DepthBiasState, TextureUsages, TextureFormat, StencilState, TextureView, Operations, Instance, Features, Extent3d, Surface, StoreOp, LoadOp, Limits, Device, Queue, Trace};
+use wgpu::{TexelCopyBufferLayout, TexelCopyTextureInfo, TexelCopyBufferInfo, CompositeAlphaMode, BufferDescriptor, TextureAspect, BufferUsages, PresentMode, Origin3d, PollType, MapMode, Adapter, Texture, COPY_BYTES_PER_ROW_ALIGNMENT, RenderPassDepthStencilAttachment, RenderPassColorAttachment, CommandEncoderDescriptor, TextureViewDescriptor, RequestAdapterOptions, SurfaceConfiguration, RenderPassDescriptor, InstanceDescriptor, DepthStencilState, TextureDescriptor, TextureDimension, MultisampleState, DeviceDescriptor, PowerPreference, CompareFunction, WindowHandle, DepthBiasState, TextureUsages, TextureFormat, StencilState, TextureView, Operations, Instance, Features, Extent3d, Surface, StoreOp, LoadOp, Limits, Device, Queue, Trace};
 
 use std::sync::Arc;
 
@@ -6,9 +6,20 @@ use wgpu_canvas::{Renderer, Atlas, Area, Item};
 
 const SAMPLE_COUNT: u32 = 4;
 
+/// Format of offscreen targets, chosen so frames can be read back as [`image::RgbaImage`]s.
+const OFFSCREEN_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// Where a [`Canvas`] presents its frames.
+enum Target {
+    /// A window surface.
+    Surface(Surface<'static>),
+    /// An offscreen texture that can be read back with [`Canvas::read`].
+    Texture(Texture),
+}
+
 pub struct Canvas {
     _instance: Instance,
-    surface: Surface<'static>,
+    target: Target,
     device: Device,
     queue: Queue,
     config: SurfaceConfiguration,
@@ -34,21 +45,8 @@ impl Canvas {
             },
         ).await.unwrap();
 
-        let mut limits = Limits::downlevel_webgl2_defaults();
-        limits.max_texture_dimension_2d = if cfg!(target_os = "android") {4096} else {8192};
-
-        let width = width.min(limits.max_texture_dimension_2d);
-        let height = height.min(limits.max_texture_dimension_2d);
-
-        let (device, queue) = adapter.request_device(
-            &DeviceDescriptor {
-                required_features: Features::empty(),
-                required_limits: limits,
-                label: None,
-                memory_hints: Default::default(),
-                trace: Trace::Off
-            }
-        ).await.unwrap();
+        let (device, queue, (width, height)) = Self::request_device(&adapter, width, height).await
+            .expect("Failed to create a wgpu device");
 
         let surface_caps = surface.get_capabilities(&adapter);
 
@@ -65,6 +63,68 @@ impl Canvas {
 
         surface.configure(&device, &config);
 
+        Self::create(instance, Target::Surface(surface), device, queue, config)
+    }
+
+    /// Creates a new `Canvas` that renders into an offscreen texture instead of a window.
+    ///
+    /// Prefers a hardware adapter and falls back to a software one (such as llvmpipe or WARP).
+    /// Returns `None` if no adapter is available at all.
+    pub async fn offscreen(width: u32, height: u32) -> Option<(Self, (u32, u32))> {
+        let instance = Instance::new(&InstanceDescriptor::default());
+
+        //A hardware adapter can exist without being able to create a device, so fall back then too
+        let mut found = None;
+        for force_fallback_adapter in [false, true] {
+            if found.is_none() && let Ok(adapter) = instance.request_adapter(
+                &RequestAdapterOptions {
+                    power_preference: PowerPreference::None,
+                    compatible_surface: None,
+                    force_fallback_adapter,
+                },
+            ).await {
+                found = Self::request_device(&adapter, width, height).await;
+            }
+        }
+        let (device, queue, (width, height)) = found?;
+
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            width,
+            height,
+            format: OFFSCREEN_FORMAT,
+            present_mode: PresentMode::Fifo,
+            alpha_mode: CompositeAlphaMode::Auto,
+            view_formats: vec![OFFSCREEN_FORMAT],
+            desired_maximum_frame_latency: 2,
+        };
+
+        let texture = Self::create_target_texture(&device, &config);
+
+        Some(Self::create(instance, Target::Texture(texture), device, queue, config))
+    }
+
+    async fn request_device(adapter: &Adapter, width: u32, height: u32) -> Option<(Device, Queue, (u32, u32))> {
+        let mut limits = Limits::downlevel_webgl2_defaults();
+        limits.max_texture_dimension_2d = if cfg!(target_os = "android") {4096} else {8192};
+
+        let width = width.min(limits.max_texture_dimension_2d);
+        let height = height.min(limits.max_texture_dimension_2d);
+
+        let (device, queue) = adapter.request_device(
+            &DeviceDescriptor {
+                required_features: Features::empty(),
+                required_limits: limits,
+                label: None,
+                memory_hints: Default::default(),
+                trace: Trace::Off
+            }
+        ).await.ok()?;
+
+        Some((device, queue, (width, height)))
+    }
+
+    fn create(instance: Instance, target: Target, device: Device, queue: Queue, config: SurfaceConfiguration) -> (Self, (u32, u32)) {
         let multisample = MultisampleState {
             count: SAMPLE_COUNT,
             mask: !0,
@@ -83,13 +143,13 @@ impl Canvas {
 
         let depth_view = Self::create_depth_view(&device, &config);
 
-        let renderer = Renderer::new(&device, &surface_caps.formats[0], multisample, Some(depth_stencil));
+        let renderer = Renderer::new(&device, &config.format, multisample, Some(depth_stencil));
 
         let size = (config.width, config.height);
 
         (Canvas{
             _instance: instance,
-            surface,
+            target,
             device,
             queue,
             config,
@@ -113,7 +173,10 @@ impl Canvas {
             let limits = self.device.limits();
             self.config.width = width.min(limits.max_texture_dimension_2d);
             self.config.height = height.min(limits.max_texture_dimension_2d);
-            self.surface.configure(&self.device, &self.config);
+            match &mut self.target {
+                Target::Surface(surface) => surface.configure(&self.device, &self.config),
+                Target::Texture(texture) => *texture = Self::create_target_texture(&self.device, &self.config),
+            }
             if SAMPLE_COUNT > 1 {
                 self.msaa_view = Some(Self::create_msaa_view(&self.device, &self.config));
             }
@@ -135,8 +198,14 @@ impl Canvas {
             atlas, items
         );
 
-        let output = self.surface.get_current_texture().unwrap();
-        let frame_view = output.texture.create_view(&TextureViewDescriptor::default());
+        let (output, frame_view) = match &self.target {
+            Target::Surface(surface) => {
+                let output = surface.get_current_texture().unwrap();
+                let frame_view = output.texture.create_view(&TextureViewDescriptor::default());
+                (Some(output), frame_view)
+            },
+            Target::Texture(texture) => (None, texture.create_view(&TextureViewDescriptor::default())),
+        };
         let mut encoder = self.device.create_command_encoder(&CommandEncoderDescriptor::default());
         let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
             label: Some("Render Pass"),
@@ -165,7 +234,78 @@ impl Canvas {
         drop(rpass);
 
         self.queue.submit(Some(encoder.finish()));
-        output.present();
+        if let Some(output) = output {
+            output.present();
+        }
+    }
+
+    /// Reads the last drawn frame back from an offscreen canvas.
+    ///
+    /// Returns `None` if this canvas presents to a window surface.
+    pub fn read(&self) -> Option<image::RgbaImage> {
+        let Target::Texture(texture) = &self.target else { return None; };
+        let (width, height) = (self.config.width, self.config.height);
+        let row = width * 4;
+        let padded_row = row.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_row * height) as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&CommandEncoderDescriptor::default());
+        encoder.copy_texture_to_buffer(
+            TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(MapMode::Read, |_| {});
+        self.device.poll(PollType::Wait).unwrap();
+
+        let pixels = slice.get_mapped_range().chunks(padded_row as usize)
+            .flat_map(|r| r[..row as usize].to_vec())
+            .collect::<Vec<_>>();
+        buffer.unmap();
+
+        image::RgbaImage::from_raw(width, height, pixels)
+    }
+
+    fn create_target_texture(device: &Device, config: &SurfaceConfiguration) -> Texture {
+        device.create_texture(&TextureDescriptor {
+            label: Some("Offscreen Target Texture"),
+            size: Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
+        })
     }
 
     fn create_msaa_view(device: &Device, config: &SurfaceConfiguration) -> TextureView {